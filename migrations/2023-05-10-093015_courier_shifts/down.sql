@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE courier_shifts;
+ALTER TABLE couriers DROP COLUMN is_on_shift;
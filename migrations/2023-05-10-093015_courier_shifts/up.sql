@@ -0,0 +1,28 @@
+ALTER TABLE couriers ADD COLUMN is_on_shift BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE courier_shifts (
+    id BIGSERIAL PRIMARY KEY,
+    courier_uuid UUID NOT NULL,
+    status TEXT NOT NULL DEFAULT 'ACTIVE',
+    started_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    paused_at TIMESTAMP,
+    paused_seconds BIGINT NOT NULL DEFAULT 0,
+    finished_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    CONSTRAINT FK_COURIER
+        FOREIGN KEY(courier_uuid)
+            REFERENCES couriers(user_uuid),
+    CONSTRAINT SHIFT_STATUS_CHECK
+        CHECK (status in ('ACTIVE', 'PAUSED', 'FINISHED'))
+);
+
+-- Courier can have only one unfinished shift at a time
+CREATE UNIQUE INDEX courier_shifts_one_open_shift
+ON courier_shifts (courier_uuid)
+WHERE status <> 'FINISHED';
+
+CREATE OR REPLACE TRIGGER set_timestamp_courier_shifts
+BEFORE UPDATE ON courier_shifts
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
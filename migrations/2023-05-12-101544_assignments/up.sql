@@ -0,0 +1,31 @@
+CREATE TABLE assignments (
+    id BIGSERIAL PRIMARY KEY,
+    user_uuid UUID NOT NULL,
+    courier_uuid UUID NOT NULL,
+    queue_id BIGINT,
+    status TEXT NOT NULL DEFAULT 'ASSIGNED',
+    assigned_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    picked_up_at TIMESTAMP,
+    delivered_at TIMESTAMP,
+    cancelled_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    CONSTRAINT FK_USER
+        FOREIGN KEY(user_uuid)
+            REFERENCES users(uuid),
+    CONSTRAINT FK_COURIER
+        FOREIGN KEY(courier_uuid)
+            REFERENCES couriers(user_uuid),
+    CONSTRAINT FK_QUEUE
+        FOREIGN KEY(queue_id)
+            REFERENCES users_queue(id),
+    CONSTRAINT ASSIGNMENT_STATUS_CHECK
+        CHECK (status in ('ASSIGNED', 'PICKED_UP', 'DELIVERED', 'CANCELLED'))
+);
+
+CREATE INDEX assignments_courier_status ON assignments (courier_uuid, status);
+
+CREATE OR REPLACE TRIGGER set_timestamp_assignments
+BEFORE UPDATE ON assignments
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
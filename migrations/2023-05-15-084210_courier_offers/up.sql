@@ -0,0 +1,34 @@
+CREATE TABLE courier_offers (
+    id BIGSERIAL PRIMARY KEY,
+    queue_id BIGINT NOT NULL,
+    user_uuid UUID NOT NULL,
+    courier_uuid UUID NOT NULL,
+    status TEXT NOT NULL DEFAULT 'PENDING',
+    expires_at TIMESTAMP NOT NULL,
+    responded_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    CONSTRAINT FK_QUEUE
+        FOREIGN KEY(queue_id)
+            REFERENCES users_queue(id),
+    CONSTRAINT FK_COURIER
+        FOREIGN KEY(courier_uuid)
+            REFERENCES couriers(user_uuid),
+    CONSTRAINT OFFER_STATUS_CHECK
+        CHECK (status in ('PENDING', 'ACCEPTED', 'DECLINED', 'EXPIRED'))
+);
+
+-- Position in queue can be offered to one courier at a time
+-- and to every courier only once
+CREATE UNIQUE INDEX courier_offers_one_pending_per_queue
+ON courier_offers (queue_id)
+WHERE status = 'PENDING';
+
+CREATE UNIQUE INDEX courier_offers_queue_courier ON courier_offers (queue_id, courier_uuid);
+
+CREATE INDEX courier_offers_courier_status ON courier_offers (courier_uuid, status);
+
+CREATE OR REPLACE TRIGGER set_timestamp_courier_offers
+BEFORE UPDATE ON courier_offers
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE users_queue
+    DROP COLUMN pickup_latitude,
+    DROP COLUMN pickup_longitude;
+DROP TABLE courier_locations;
@@ -0,0 +1,22 @@
+CREATE TABLE courier_locations (
+    courier_uuid UUID PRIMARY KEY NOT NULL,
+    latitude DOUBLE PRECISION NOT NULL,
+    longitude DOUBLE PRECISION NOT NULL,
+    recorded_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    CONSTRAINT FK_COURIER
+        FOREIGN KEY(courier_uuid)
+            REFERENCES couriers(user_uuid),
+    CONSTRAINT CHECK_COORDINATES
+        CHECK (latitude between -90.0 and 90.0 and longitude between -180.0 and 180.0)
+);
+
+CREATE OR REPLACE TRIGGER set_timestamp_courier_locations
+BEFORE UPDATE ON courier_locations
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+ALTER TABLE users_queue
+    ADD COLUMN pickup_latitude DOUBLE PRECISION,
+    ADD COLUMN pickup_longitude DOUBLE PRECISION;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE couriers DROP COLUMN ratings_count;
+DROP TABLE courier_ratings;
@@ -0,0 +1,24 @@
+CREATE TABLE courier_ratings (
+    id BIGSERIAL PRIMARY KEY,
+    assignment_id BIGINT NOT NULL UNIQUE,
+    courier_uuid UUID NOT NULL,
+    rater_uuid UUID NOT NULL,
+    score FLOAT NOT NULL,
+    comment TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    CONSTRAINT FK_ASSIGNMENT
+        FOREIGN KEY(assignment_id)
+            REFERENCES assignments(id),
+    CONSTRAINT FK_COURIER
+        FOREIGN KEY(courier_uuid)
+            REFERENCES couriers(user_uuid),
+    CONSTRAINT FK_RATER
+        FOREIGN KEY(rater_uuid)
+            REFERENCES users(uuid),
+    CONSTRAINT CHECK_SCORE
+        CHECK (score between 0.0 and 5.0)
+);
+
+CREATE INDEX courier_ratings_courier ON courier_ratings (courier_uuid, created_at);
+
+ALTER TABLE couriers ADD COLUMN ratings_count BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE users_queue
+    DROP COLUMN order_weight_kg,
+    DROP COLUMN order_volume_liters;
+
+DROP TABLE courier_vehicles;
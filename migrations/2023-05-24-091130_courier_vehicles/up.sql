@@ -0,0 +1,26 @@
+CREATE TABLE courier_vehicles (
+    courier_uuid UUID PRIMARY KEY NOT NULL,
+    vehicle_type TEXT NOT NULL,
+    max_weight_kg DOUBLE PRECISION NOT NULL,
+    max_volume_liters DOUBLE PRECISION NOT NULL,
+    max_concurrent_deliveries INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    CONSTRAINT FK_COURIER
+        FOREIGN KEY(courier_uuid)
+            REFERENCES couriers(user_uuid),
+    CONSTRAINT VEHICLE_TYPE_CHECK
+        CHECK (vehicle_type in ('FOOT', 'BIKE', 'SCOOTER', 'CAR')),
+    CONSTRAINT CAPACITY_CHECK
+        CHECK (max_weight_kg > 0 and max_volume_liters > 0 and max_concurrent_deliveries > 0)
+);
+
+CREATE OR REPLACE TRIGGER set_timestamp_courier_vehicles
+BEFORE UPDATE ON courier_vehicles
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+-- Size requirements of the order, unknown for orders of any size
+ALTER TABLE users_queue
+    ADD COLUMN order_weight_kg DOUBLE PRECISION,
+    ADD COLUMN order_volume_liters DOUBLE PRECISION;
@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE courier_documents;
+
+ALTER TABLE couriers
+    DROP CONSTRAINT ONBOARDING_STATUS_CHECK,
+    DROP COLUMN rejection_reason,
+    DROP COLUMN onboarding_status;
@@ -0,0 +1,33 @@
+ALTER TABLE couriers
+    ADD COLUMN onboarding_status TEXT NOT NULL DEFAULT 'PENDING_DOCUMENTS',
+    ADD COLUMN rejection_reason TEXT,
+    ADD CONSTRAINT ONBOARDING_STATUS_CHECK
+        CHECK (onboarding_status in ('PENDING_DOCUMENTS', 'UNDER_REVIEW', 'APPROVED', 'REJECTED'));
+
+-- Couriers registered before onboarding was introduced are already working
+UPDATE couriers SET onboarding_status = 'APPROVED';
+
+CREATE TABLE courier_documents (
+    id BIGSERIAL PRIMARY KEY,
+    courier_uuid UUID NOT NULL,
+    document_type TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    storage_key TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    CONSTRAINT FK_COURIER
+        FOREIGN KEY(courier_uuid)
+            REFERENCES couriers(user_uuid),
+    CONSTRAINT DOCUMENT_TYPE_CHECK
+        CHECK (document_type in ('ID', 'DRIVING_LICENSE'))
+);
+
+-- Uploading document of the same type replaces the previous one
+CREATE UNIQUE INDEX courier_documents_one_per_type
+ON courier_documents (courier_uuid, document_type);
+
+CREATE OR REPLACE TRIGGER set_timestamp_courier_documents
+BEFORE UPDATE ON courier_documents
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
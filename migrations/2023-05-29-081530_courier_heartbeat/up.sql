@@ -0,0 +1,4 @@
+ALTER TABLE couriers ADD COLUMN last_seen_at TIMESTAMP;
+
+-- Giving couriers who are on shift right now time to send their first heartbeat
+UPDATE couriers SET last_seen_at = NOW() WHERE is_on_shift;
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE users_queue
+    DROP CONSTRAINT FK_ZONE,
+    DROP COLUMN zone_id;
+
+DROP TABLE courier_zones;
+DROP TABLE zone_neighbours;
+DROP TABLE zones;
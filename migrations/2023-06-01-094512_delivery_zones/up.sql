@@ -0,0 +1,51 @@
+-- Polygon is a JSON array of points like {"latitude": 55.75, "longitude": 37.61}
+CREATE TABLE zones (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    polygon JSONB NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE OR REPLACE TRIGGER set_timestamp_zones
+BEFORE UPDATE ON zones
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
+
+-- Neighbourhood is symmetric, both directions are stored
+CREATE TABLE zone_neighbours (
+    zone_id BIGINT NOT NULL,
+    neighbour_id BIGINT NOT NULL,
+    PRIMARY KEY (zone_id, neighbour_id),
+    CONSTRAINT FK_ZONE
+        FOREIGN KEY(zone_id)
+            REFERENCES zones(id)
+            ON DELETE CASCADE,
+    CONSTRAINT FK_NEIGHBOUR
+        FOREIGN KEY(neighbour_id)
+            REFERENCES zones(id)
+            ON DELETE CASCADE,
+    CONSTRAINT CHECK_NOT_SELF
+        CHECK (zone_id <> neighbour_id)
+);
+
+CREATE TABLE courier_zones (
+    courier_uuid UUID NOT NULL,
+    zone_id BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (courier_uuid, zone_id),
+    CONSTRAINT FK_COURIER
+        FOREIGN KEY(courier_uuid)
+            REFERENCES couriers(user_uuid),
+    CONSTRAINT FK_ZONE
+        FOREIGN KEY(zone_id)
+            REFERENCES zones(id)
+            ON DELETE CASCADE
+);
+
+ALTER TABLE users_queue
+    ADD COLUMN zone_id BIGINT,
+    ADD CONSTRAINT FK_ZONE
+        FOREIGN KEY(zone_id)
+            REFERENCES zones(id)
+            ON DELETE SET NULL;
@@ -0,0 +1,43 @@
+-- Weekly windows when courier is ready to work, day_of_week is ISO 8601 (1 is Monday)
+CREATE TABLE courier_availability (
+    id BIGSERIAL PRIMARY KEY,
+    courier_uuid UUID NOT NULL,
+    day_of_week SMALLINT NOT NULL,
+    start_time TIME NOT NULL,
+    end_time TIME NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    CONSTRAINT FK_COURIER
+        FOREIGN KEY(courier_uuid)
+            REFERENCES couriers(user_uuid),
+    CONSTRAINT CHECK_DAY_OF_WEEK
+        CHECK (day_of_week between 1 and 7),
+    CONSTRAINT CHECK_WINDOW
+        CHECK (start_time < end_time)
+);
+
+CREATE INDEX courier_availability_courier ON courier_availability (courier_uuid);
+
+CREATE TABLE planned_shifts (
+    id BIGSERIAL PRIMARY KEY,
+    courier_uuid UUID NOT NULL,
+    starts_at TIMESTAMP NOT NULL,
+    ends_at TIMESTAMP NOT NULL,
+    created_by UUID NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    CONSTRAINT FK_COURIER
+        FOREIGN KEY(courier_uuid)
+            REFERENCES couriers(user_uuid),
+    CONSTRAINT FK_CREATOR
+        FOREIGN KEY(created_by)
+            REFERENCES users(uuid),
+    CONSTRAINT CHECK_PERIOD
+        CHECK (starts_at < ends_at)
+);
+
+CREATE INDEX planned_shifts_period ON planned_shifts (starts_at, ends_at);
+
+CREATE OR REPLACE TRIGGER set_timestamp_planned_shifts
+BEFORE UPDATE ON planned_shifts
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+UPDATE courier_offers SET status = 'EXPIRED' WHERE status = 'WITHDRAWN';
+
+ALTER TABLE courier_offers DROP CONSTRAINT OFFER_STATUS_CHECK;
+
+ALTER TABLE courier_offers ADD CONSTRAINT OFFER_STATUS_CHECK
+    CHECK (status in ('PENDING', 'ACCEPTED', 'DECLINED', 'EXPIRED'));
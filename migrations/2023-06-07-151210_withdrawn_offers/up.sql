@@ -0,0 +1,5 @@
+-- Offers of positions cancelled by user are withdrawn, they don't count against courier
+ALTER TABLE courier_offers DROP CONSTRAINT OFFER_STATUS_CHECK;
+
+ALTER TABLE courier_offers ADD CONSTRAINT OFFER_STATUS_CHECK
+    CHECK (status in ('PENDING', 'ACCEPTED', 'DECLINED', 'EXPIRED', 'WITHDRAWN'));
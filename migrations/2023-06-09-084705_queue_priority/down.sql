@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX users_queue_searching;
+
+ALTER TABLE users_queue DROP COLUMN priority;
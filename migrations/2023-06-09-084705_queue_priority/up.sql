@@ -0,0 +1,8 @@
+-- Higher priority goes first, waiting time ages it so low priority can't starve
+ALTER TABLE users_queue ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE users_queue ADD CONSTRAINT CHECK_PRIORITY
+    CHECK (priority between 0 and 10);
+
+CREATE INDEX users_queue_searching ON users_queue (created_at, id)
+WHERE status = 'SEARCHING';
@@ -0,0 +1,7 @@
+-- Current leader of every background job, leadership itself is an advisory lock
+CREATE TABLE service_leaders (
+    name TEXT PRIMARY KEY,
+    instance_id TEXT NOT NULL,
+    elected_at TIMESTAMP NOT NULL,
+    heartbeat_at TIMESTAMP NOT NULL
+);
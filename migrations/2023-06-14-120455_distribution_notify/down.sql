@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER notify_distribution_courier_offers ON courier_offers;
+DROP TRIGGER notify_distribution_couriers ON couriers;
+DROP TRIGGER notify_distribution_users_queue ON users_queue;
+DROP FUNCTION notify_courier_distribution();
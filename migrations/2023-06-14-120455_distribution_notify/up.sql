@@ -0,0 +1,25 @@
+-- Wakes courier distributor up when there can be something to distribute
+CREATE OR REPLACE FUNCTION notify_courier_distribution()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('courier_distribution', TG_TABLE_NAME);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER notify_distribution_users_queue
+AFTER INSERT OR UPDATE OF status ON users_queue
+FOR EACH ROW
+EXECUTE PROCEDURE notify_courier_distribution();
+
+-- Only courier who became available is interesting
+CREATE OR REPLACE TRIGGER notify_distribution_couriers
+AFTER UPDATE OF is_free, is_on_shift ON couriers
+FOR EACH ROW
+WHEN (NEW.is_free AND NEW.is_on_shift AND NOT (OLD.is_free AND OLD.is_on_shift))
+EXECUTE PROCEDURE notify_courier_distribution();
+
+CREATE OR REPLACE TRIGGER notify_distribution_courier_offers
+AFTER UPDATE OF status ON courier_offers
+FOR EACH ROW
+EXECUTE PROCEDURE notify_courier_distribution();
@@ -0,0 +1,27 @@
+-- Notifications for other services are written in the same transaction
+-- as the change they are about and delivered by the outbox dispatcher
+CREATE TABLE outbox_messages (
+    id BIGSERIAL PRIMARY KEY,
+    kind TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    status TEXT NOT NULL DEFAULT 'PENDING',
+    attempts INT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    CONSTRAINT OUTBOX_STATUS_CHECK
+        CHECK (status in ('PENDING', 'DELIVERED', 'DEAD'))
+);
+
+CREATE INDEX outbox_messages_pending
+ON outbox_messages (next_attempt_at)
+WHERE status = 'PENDING';
+
+CREATE INDEX outbox_messages_status ON outbox_messages (status, created_at);
+
+CREATE OR REPLACE TRIGGER set_timestamp_outbox_messages
+BEFORE UPDATE ON outbox_messages
+FOR EACH ROW
+EXECUTE PROCEDURE trigger_set_timestamp();
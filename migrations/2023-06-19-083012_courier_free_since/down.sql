@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER set_free_since_couriers ON couriers;
+DROP FUNCTION set_courier_free_since();
+ALTER TABLE couriers DROP COLUMN free_since;
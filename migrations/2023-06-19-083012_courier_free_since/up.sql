@@ -0,0 +1,19 @@
+-- Free couriers are offered orders in order of becoming available.
+-- updated_at can't be used for it, heartbeats and location pings bump it
+ALTER TABLE couriers ADD COLUMN free_since TIMESTAMP NOT NULL DEFAULT NOW();
+
+UPDATE couriers SET free_since = updated_at;
+
+CREATE OR REPLACE FUNCTION set_courier_free_since()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.free_since = NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER set_free_since_couriers
+BEFORE UPDATE OF is_free, is_on_shift ON couriers
+FOR EACH ROW
+WHEN (NEW.is_free AND NEW.is_on_shift AND NOT (OLD.is_free AND OLD.is_on_shift))
+EXECUTE PROCEDURE set_courier_free_since();
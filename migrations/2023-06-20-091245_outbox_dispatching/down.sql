@@ -0,0 +1,13 @@
+-- This file should undo anything in `up.sql`
+UPDATE outbox_messages SET status = 'PENDING' WHERE status = 'DISPATCHING';
+
+DROP INDEX outbox_messages_due;
+
+CREATE INDEX outbox_messages_pending
+ON outbox_messages (next_attempt_at)
+WHERE status = 'PENDING';
+
+ALTER TABLE outbox_messages DROP CONSTRAINT OUTBOX_STATUS_CHECK;
+
+ALTER TABLE outbox_messages ADD CONSTRAINT OUTBOX_STATUS_CHECK
+    CHECK (status in ('PENDING', 'DELIVERED', 'DEAD'));
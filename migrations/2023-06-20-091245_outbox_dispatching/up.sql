@@ -0,0 +1,12 @@
+-- Messages are claimed for delivery with a lease instead of being locked
+-- for the whole delivery. Claim of a dispatcher which died expires with the lease
+ALTER TABLE outbox_messages DROP CONSTRAINT OUTBOX_STATUS_CHECK;
+
+ALTER TABLE outbox_messages ADD CONSTRAINT OUTBOX_STATUS_CHECK
+    CHECK (status in ('PENDING', 'DISPATCHING', 'DELIVERED', 'DEAD'));
+
+DROP INDEX outbox_messages_pending;
+
+CREATE INDEX outbox_messages_due
+ON outbox_messages (next_attempt_at)
+WHERE status IN ('PENDING', 'DISPATCHING');
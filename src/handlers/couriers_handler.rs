@@ -1,9 +1,24 @@
+use crate::models::documents_model::{DocumentType, OnboardingQuery, RejectCourier};
+use crate::models::locations_model::LocationPingsBatch;
+use crate::models::ratings_model::RatingsPageRequest;
+use crate::models::schedules_model::{
+    AvailabilityForm, CapacityForecastQuery, PlannedShiftForm, PlannedShiftsQuery,
+};
+use crate::models::stats_model::StatsPeriodQuery;
+use crate::models::vehicles_model::VehicleProfile;
+use crate::models::zones_model::CourierZonesForm;
 use crate::repository::couriers_repository;
 use crate::resources::postgres::{execute_connection, DbPool};
 use crate::services::auth_service::TokenClaims;
+use crate::services::{
+    assignments_service, locations_service, offers_service, onboarding_service, presence_service,
+    ratings_service, schedules_service, shifts_service, stats_service, vehicles_service,
+    zones_service,
+};
+use crate::utils::configs::Config;
 use crate::utils::errors::AppError;
 use actix_web::web::ReqData;
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
 use uuid::Uuid;
 
 pub async fn get_all_couriers(pool: web::Data<DbPool>) -> Result<impl Responder, AppError> {
@@ -37,3 +52,451 @@ pub async fn get_courier_profile(
         .map_err(AppError::db_error)?;
     Ok(HttpResponse::Ok().body(serde_json::to_string(&user).map_err(AppError::serde_error)?))
 }
+
+pub async fn start_shift(
+    pool: web::Data<DbPool>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let shift = shifts_service::start_shift(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&shift).map_err(AppError::serde_error)?))
+}
+
+pub async fn pause_shift(
+    pool: web::Data<DbPool>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let shift = shifts_service::pause_shift(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&shift).map_err(AppError::serde_error)?))
+}
+
+pub async fn end_shift(
+    pool: web::Data<DbPool>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let shift = shifts_service::end_shift(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&shift).map_err(AppError::serde_error)?))
+}
+
+pub async fn heartbeat(
+    pool: web::Data<DbPool>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let heartbeat = presence_service::heartbeat(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&heartbeat).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_shifts_history(
+    pool: web::Data<DbPool>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let shifts = shifts_service::get_shifts_history(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&shifts).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_active_assignments(
+    pool: web::Data<DbPool>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let assignments = assignments_service::get_active_assignments(&mut db_conn, uuid).await?;
+    Ok(
+        HttpResponse::Ok()
+            .body(serde_json::to_string(&assignments).map_err(AppError::serde_error)?),
+    )
+}
+
+pub async fn pick_up_assignment(
+    pool: web::Data<DbPool>,
+    path: web::Path<i64>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let assignment_id = path.into_inner();
+    let assignment =
+        assignments_service::pick_up_assignment(&mut db_conn, assignment_id, Some(uuid)).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&assignment).map_err(AppError::serde_error)?))
+}
+
+pub async fn deliver_assignment(
+    pool: web::Data<DbPool>,
+    path: web::Path<i64>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let assignment_id = path.into_inner();
+    let assignment =
+        assignments_service::deliver_assignment(&mut db_conn, assignment_id, Some(uuid)).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&assignment).map_err(AppError::serde_error)?))
+}
+
+pub async fn cancel_assignment(
+    pool: web::Data<DbPool>,
+    path: web::Path<i64>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let assignment_id = path.into_inner();
+    let assignment =
+        assignments_service::cancel_assignment(&mut db_conn, assignment_id, Some(uuid)).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&assignment).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_courier_offers(
+    pool: web::Data<DbPool>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let offers = offers_service::get_courier_offers(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&offers).map_err(AppError::serde_error)?))
+}
+
+pub async fn accept_offer(
+    pool: web::Data<DbPool>,
+    path: web::Path<i64>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let offer_id = path.into_inner();
+    let assignment = offers_service::accept_offer(&mut db_conn, offer_id, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&assignment).map_err(AppError::serde_error)?))
+}
+
+pub async fn decline_offer(
+    pool: web::Data<DbPool>,
+    path: web::Path<i64>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let offer_id = path.into_inner();
+    let offer = offers_service::decline_offer(&mut db_conn, offer_id, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&offer).map_err(AppError::serde_error)?))
+}
+
+pub async fn report_locations(
+    pool: web::Data<DbPool>,
+    data: actix_web_validator::Json<LocationPingsBatch>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let pings = data.into_inner().pings;
+    let location = locations_service::save_location_pings(&mut db_conn, uuid, pings).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&location).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_vehicle_profile(
+    pool: web::Data<DbPool>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let vehicle = vehicles_service::get_vehicle_profile(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&vehicle).map_err(AppError::serde_error)?))
+}
+
+pub async fn set_vehicle_profile(
+    pool: web::Data<DbPool>,
+    data: actix_web_validator::Json<VehicleProfile>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let vehicle =
+        vehicles_service::set_vehicle_profile(&mut db_conn, uuid, data.into_inner()).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&vehicle).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_courier_stats(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    query: web::Query<StatsPeriodQuery>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let stats =
+        stats_service::get_courier_stats(&config, &mut db_conn, uuid, query.into_inner()).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&stats).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_courier_stats_by_uuid(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    path: web::Path<Uuid>,
+    query: web::Query<StatsPeriodQuery>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = path.into_inner();
+    let stats =
+        stats_service::get_courier_stats(&config, &mut db_conn, uuid, query.into_inner()).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&stats).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_own_zones(
+    pool: web::Data<DbPool>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let zones = zones_service::get_courier_zones(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&zones).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_courier_zones(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = path.into_inner();
+    let zones = zones_service::get_courier_zones(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&zones).map_err(AppError::serde_error)?))
+}
+
+pub async fn set_courier_zones(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    data: actix_web_validator::Json<CourierZonesForm>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = path.into_inner();
+    let zones =
+        zones_service::set_courier_zones(&mut db_conn, uuid, data.into_inner().zones).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&zones).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_couriers_ratings(
+    pool: web::Data<DbPool>,
+    query: actix_web_validator::Query<RatingsPageRequest>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let page = ratings_service::list_courier_ratings(&mut db_conn, query.into_inner()).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&page).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_courier_rating(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = path.into_inner();
+    let rating = ratings_service::get_courier_rating(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&rating).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_onboarding_info(
+    pool: web::Data<DbPool>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let onboarding = onboarding_service::get_onboarding_info(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&onboarding).map_err(AppError::serde_error)?))
+}
+
+// Document is sent as raw bytes with its Content-Type
+pub async fn upload_document(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    path: web::Path<String>,
+    req: HttpRequest,
+    body: web::Bytes,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let document_type = path
+        .into_inner()
+        .parse::<DocumentType>()
+        .map_err(AppError::validation_error)?;
+    let document = onboarding_service::upload_document(
+        config.documents_storage.as_ref(),
+        &mut db_conn,
+        uuid,
+        document_type,
+        req.content_type(),
+        &body,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&document).map_err(AppError::serde_error)?))
+}
+
+pub async fn submit_onboarding(
+    pool: web::Data<DbPool>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let onboarding = onboarding_service::submit_for_review(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&onboarding).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_couriers_onboarding(
+    pool: web::Data<DbPool>,
+    query: web::Query<OnboardingQuery>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let status = query.into_inner().status;
+    let couriers =
+        onboarding_service::get_couriers_by_onboarding_status(&mut db_conn, status).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&couriers).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_courier_onboarding(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = path.into_inner();
+    let onboarding = onboarding_service::get_onboarding_info(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&onboarding).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_courier_document(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    path: web::Path<(Uuid, i64)>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let (uuid, document_id) = path.into_inner();
+    let (document, content) = onboarding_service::get_document_content(
+        config.documents_storage.as_ref(),
+        &mut db_conn,
+        uuid,
+        document_id,
+    )
+    .await?;
+    Ok(HttpResponse::Ok()
+        .content_type(document.content_type)
+        .body(content))
+}
+
+pub async fn approve_courier(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = path.into_inner();
+    let onboarding = onboarding_service::approve_courier(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&onboarding).map_err(AppError::serde_error)?))
+}
+
+pub async fn reject_courier(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    data: actix_web_validator::Json<RejectCourier>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = path.into_inner();
+    let reason = data.into_inner().reason;
+    let onboarding = onboarding_service::reject_courier(&mut db_conn, uuid, reason).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&onboarding).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_availability(
+    pool: web::Data<DbPool>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let windows = schedules_service::get_availability(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&windows).map_err(AppError::serde_error)?))
+}
+
+pub async fn set_availability(
+    pool: web::Data<DbPool>,
+    data: actix_web_validator::Json<AvailabilityForm>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let windows =
+        schedules_service::set_availability(&mut db_conn, uuid, data.into_inner().windows).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&windows).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_own_planned_shifts(
+    pool: web::Data<DbPool>,
+    query: web::Query<PlannedShiftsQuery>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let shifts =
+        schedules_service::get_planned_shifts(&mut db_conn, Some(uuid), query.into_inner()).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&shifts).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_planned_shifts(
+    pool: web::Data<DbPool>,
+    query: web::Query<PlannedShiftsQuery>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let shifts =
+        schedules_service::get_planned_shifts(&mut db_conn, None, query.into_inner()).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&shifts).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_courier_planned_shifts(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    query: web::Query<PlannedShiftsQuery>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = path.into_inner();
+    let shifts =
+        schedules_service::get_planned_shifts(&mut db_conn, Some(uuid), query.into_inner()).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&shifts).map_err(AppError::serde_error)?))
+}
+
+pub async fn create_planned_shift(
+    pool: web::Data<DbPool>,
+    path: web::Path<Uuid>,
+    data: web::Json<PlannedShiftForm>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = path.into_inner();
+    let admin = req_user.unwrap().uuid;
+    let shift =
+        schedules_service::create_planned_shift(&mut db_conn, uuid, admin, data.into_inner())
+            .await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&shift).map_err(AppError::serde_error)?))
+}
+
+pub async fn delete_planned_shift(
+    pool: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let shift_id = path.into_inner();
+    schedules_service::delete_planned_shift(&mut db_conn, shift_id).await?;
+    Ok(HttpResponse::Ok().body("Planned shift deleted"))
+}
+
+pub async fn get_capacity_forecast(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    query: actix_web_validator::Query<CapacityForecastQuery>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let forecast =
+        schedules_service::get_capacity_forecast(&config, &mut db_conn, query.into_inner()).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&forecast).map_err(AppError::serde_error)?))
+}
@@ -1,3 +1,5 @@
 pub mod auth_handler;
 pub mod couriers_handler;
+pub mod system_handler;
 pub mod users_handler;
+pub mod zones_handler;
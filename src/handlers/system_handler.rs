@@ -0,0 +1,42 @@
+use crate::models::outbox_model::OutboxMessagesQuery;
+use crate::resources::postgres::{execute_connection, DbPool};
+use crate::services::{leader_service, outbox_service};
+use crate::utils::configs::Config;
+use crate::utils::errors::AppError;
+use actix_web::{web, HttpResponse, Responder};
+
+pub async fn get_distributor_status(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let status = leader_service::get_distributor_status(&config, &mut db_conn).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&status).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_outbox_messages(
+    pool: web::Data<DbPool>,
+    query: actix_web_validator::Query<OutboxMessagesQuery>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let page = outbox_service::get_outbox_messages(&mut db_conn, query.into_inner()).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&page).map_err(AppError::serde_error)?))
+}
+
+pub async fn get_outbox_message(
+    pool: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let message = outbox_service::get_outbox_message(&mut db_conn, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&message).map_err(AppError::serde_error)?))
+}
+
+pub async fn replay_outbox_message(
+    pool: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let message = outbox_service::replay_outbox_message(&mut db_conn, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&message).map_err(AppError::serde_error)?))
+}
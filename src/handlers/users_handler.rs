@@ -2,9 +2,13 @@ use crate::models::users_model::*;
 use crate::repository::users_repository;
 use crate::resources::postgres::{execute_connection, DbPool};
 use crate::services::auth_service::TokenClaims;
+use crate::services::{queue_service, users_service};
+use crate::utils::configs::Config;
 use crate::utils::errors::AppError;
+use actix_web::http::header;
 use actix_web::web::ReqData;
 use actix_web::{web, HttpResponse, Responder};
+use tokio_stream::StreamExt;
 use uuid::Uuid;
 
 pub async fn get_all_users(pool: web::Data<DbPool>) -> Result<impl Responder, AppError> {
@@ -76,3 +80,41 @@ pub async fn delete_user(
         .map_err(AppError::db_error)?;
     Ok(HttpResponse::Ok().body("User deleted"))
 }
+
+pub async fn cancel_courier_search(
+    pool: web::Data<DbPool>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let uuid = req_user.unwrap().uuid;
+    let position = queue_service::cancel_courier_search(&mut db_conn, uuid).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&position).map_err(AppError::serde_error)?))
+}
+
+// Server-Sent Events with search status, the stream ends after the final status
+pub async fn watch_courier_search(
+    pool: web::Data<DbPool>,
+    config: web::Data<Config>,
+    req_user: Option<ReqData<TokenClaims>>,
+) -> Result<impl Responder, AppError> {
+    let uuid = req_user.unwrap().uuid;
+    let updates = users_service::subscribe_search_status(
+        config.get_ref().clone(),
+        pool.get_ref().clone(),
+        uuid,
+    )
+    .map(|update| {
+        let event = match update {
+            Ok(search) => match serde_json::to_string(&search) {
+                Ok(data) => format!("event: status\ndata: {data}\n\n"),
+                Err(e) => format!("event: error\ndata: {e}\n\n"),
+            },
+            Err(e) => format!("event: error\ndata: {e}\n\n"),
+        };
+        Ok::<_, actix_web::Error>(web::Bytes::from(event))
+    });
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(updates))
+}
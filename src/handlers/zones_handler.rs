@@ -0,0 +1,54 @@
+use crate::models::zones_model::{ZoneForm, ZoneNeighboursForm};
+use crate::resources::postgres::{execute_connection, DbPool};
+use crate::services::zones_service;
+use crate::utils::errors::AppError;
+use actix_web::{web, HttpResponse, Responder};
+
+pub async fn get_zones(pool: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let zones = zones_service::get_zones(&mut db_conn).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&zones).map_err(AppError::serde_error)?))
+}
+
+pub async fn create_zone(
+    pool: web::Data<DbPool>,
+    data: actix_web_validator::Json<ZoneForm>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let zone = zones_service::create_zone(&mut db_conn, data.into_inner()).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&zone).map_err(AppError::serde_error)?))
+}
+
+pub async fn update_zone(
+    pool: web::Data<DbPool>,
+    path: web::Path<i64>,
+    data: actix_web_validator::Json<ZoneForm>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let zone_id = path.into_inner();
+    let zone = zones_service::update_zone(&mut db_conn, zone_id, data.into_inner()).await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&zone).map_err(AppError::serde_error)?))
+}
+
+pub async fn delete_zone(
+    pool: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let zone_id = path.into_inner();
+    zones_service::delete_zone(&mut db_conn, zone_id).await?;
+    Ok(HttpResponse::Ok().body("Zone deleted"))
+}
+
+pub async fn set_zone_neighbours(
+    pool: web::Data<DbPool>,
+    path: web::Path<i64>,
+    data: actix_web_validator::Json<ZoneNeighboursForm>,
+) -> Result<impl Responder, AppError> {
+    let mut db_conn = execute_connection(&pool).await?;
+    let zone_id = path.into_inner();
+    let neighbours =
+        zones_service::set_zone_neighbours(&mut db_conn, zone_id, data.into_inner().neighbours)
+            .await?;
+    Ok(HttpResponse::Ok().body(serde_json::to_string(&neighbours).map_err(AppError::serde_error)?))
+}
@@ -1,5 +1,6 @@
 use delivery_user::utils::configs::{
-    run_courier_distributor_untill_stopped, Application, Config, GrpcServer,
+    run_courier_distributor_untill_stopped, run_courier_sweeper_untill_stopped,
+    run_outbox_dispatcher_untill_stopped, Application, Config, GrpcServer,
 };
 use std::fmt::{Debug, Display};
 use tokio::task::JoinError;
@@ -14,12 +15,16 @@ async fn main() -> Result<(), anyhow::Error> {
 
     let application_task = tokio::spawn(application.run_untill_stopped());
     let grpc_server_task = tokio::spawn(grpc_server.run_untill_stopped(config.clone()));
+    let courier_sweeper_task = tokio::spawn(run_courier_sweeper_untill_stopped(config.clone()));
+    let outbox_dispatcher_task = tokio::spawn(run_outbox_dispatcher_untill_stopped(config.clone()));
     let courier_distributor_task = tokio::spawn(run_courier_distributor_untill_stopped(config));
 
     tokio::select! {
         task = application_task => report_exit("Application", task),
         task = grpc_server_task =>  report_exit("gRPC Server", task),
         task = courier_distributor_task =>  report_exit("Courier distributor", task),
+        task = courier_sweeper_task =>  report_exit("Courier sweeper", task),
+        task = outbox_dispatcher_task =>  report_exit("Outbox dispatcher", task),
     };
 
     Ok(())
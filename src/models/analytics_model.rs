@@ -0,0 +1,32 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// Event of delivery lifecycle reported to Analytics service
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AnalyticsEvent {
+    pub event_type: String,
+    pub user_uuid: Uuid,
+    pub courier_uuid: Option<Uuid>,
+    pub queue_id: Option<i64>,
+    pub assignment_id: Option<i64>,
+    pub occurred_at: NaiveDateTime,
+    // Waiting time for queue events, delivery time for delivery events
+    pub duration_seconds: Option<i64>,
+    pub rating: Option<f64>,
+}
+
+impl AnalyticsEvent {
+    pub fn new(event_type: &str, user_uuid: Uuid, occurred_at: NaiveDateTime) -> Self {
+        AnalyticsEvent {
+            event_type: event_type.to_string(),
+            user_uuid,
+            courier_uuid: None,
+            queue_id: None,
+            assignment_id: None,
+            occurred_at,
+            duration_seconds: None,
+            rating: None,
+        }
+    }
+}
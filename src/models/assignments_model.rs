@@ -0,0 +1,38 @@
+use crate::schema::schema::assignments;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Queryable, Serialize)]
+#[diesel(table_name = assignments)]
+pub struct Assignment {
+    pub id: i64,
+    pub user_uuid: Uuid,
+    pub courier_uuid: Uuid,
+    pub queue_id: Option<i64>,
+    pub status: String,
+    pub assigned_at: NaiveDateTime,
+    pub picked_up_at: Option<NaiveDateTime>,
+    pub delivered_at: Option<NaiveDateTime>,
+    pub cancelled_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = assignments)]
+pub struct CreateAssignment {
+    pub user_uuid: Uuid,
+    pub courier_uuid: Uuid,
+    pub queue_id: Option<i64>,
+}
+
+#[derive(AsChangeset, Default)]
+#[diesel(table_name = assignments)]
+pub struct UpdateAssignment {
+    pub status: Option<String>,
+    pub picked_up_at: Option<NaiveDateTime>,
+    pub delivered_at: Option<NaiveDateTime>,
+    pub cancelled_at: Option<NaiveDateTime>,
+}
@@ -13,6 +13,12 @@ pub struct Couriers {
     pub rating: f64,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub is_on_shift: bool,
+    pub ratings_count: i64,
+    pub onboarding_status: String,
+    pub rejection_reason: Option<String>,
+    pub last_seen_at: Option<NaiveDateTime>,
+    pub free_since: NaiveDateTime,
 }
 
 #[derive(Insertable)]
@@ -34,4 +40,34 @@ pub struct CourierInfo {
 pub struct UpdateCourier {
     pub is_free: Option<bool>,
     pub rating: Option<f64>,
+    pub is_on_shift: Option<bool>,
+}
+
+#[derive(AsChangeset, Clone)]
+#[diesel(table_name = couriers)]
+pub struct UpdateCourierRating {
+    pub rating: f64,
+    pub ratings_count: i64,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = couriers)]
+#[diesel(treat_none_as_null = true)]
+pub struct UpdateOnboardingStatus {
+    pub onboarding_status: String,
+    pub rejection_reason: Option<String>,
+}
+
+#[derive(Serialize, Queryable)]
+pub struct CourierOnboarding {
+    pub user_uuid: Uuid,
+    pub onboarding_status: String,
+    pub rejection_reason: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Queryable)]
+pub struct CourierHeartbeat {
+    pub last_seen_at: Option<NaiveDateTime>,
+    pub is_on_shift: bool,
 }
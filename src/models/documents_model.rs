@@ -0,0 +1,86 @@
+use crate::schema::schema::courier_documents;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use uuid::Uuid;
+use validator::Validate;
+
+pub const ALLOWED_CONTENT_TYPES: [&str; 3] = ["image/jpeg", "image/png", "application/pdf"];
+
+#[derive(Queryable, Serialize)]
+#[diesel(table_name = courier_documents)]
+pub struct CourierDocument {
+    pub id: i64,
+    pub courier_uuid: Uuid,
+    pub document_type: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = courier_documents)]
+pub struct UpsertCourierDocument {
+    pub courier_uuid: Uuid,
+    pub document_type: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub storage_key: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DocumentType {
+    Id,
+    DrivingLicense,
+}
+
+impl FromStr for DocumentType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "ID" => Ok(DocumentType::Id),
+            "DRIVING_LICENSE" => Ok(DocumentType::DrivingLicense),
+            _ => Err(format!(
+                "Unknown document type {value}. Must be one of: ID, DRIVING_LICENSE"
+            )),
+        }
+    }
+}
+
+impl Display for DocumentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DocumentType::Id => "ID",
+            DocumentType::DrivingLicense => "DRIVING_LICENSE",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Serialize)]
+pub struct OnboardingInfo {
+    pub onboarding_status: String,
+    pub rejection_reason: Option<String>,
+    pub documents: Vec<CourierDocument>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RejectCourier {
+    #[validate(length(
+        min = 1,
+        max = 500,
+        message = "Reason must be from 1 to 500 characters"
+    ))]
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+pub struct OnboardingQuery {
+    pub status: Option<String>,
+}
@@ -0,0 +1,31 @@
+use crate::schema::schema::service_leaders;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::sql_types::Bool;
+use serde::Serialize;
+
+#[derive(Queryable, Insertable, AsChangeset, Serialize)]
+#[diesel(table_name = service_leaders)]
+pub struct ServiceLeader {
+    pub name: String,
+    pub instance_id: String,
+    pub elected_at: NaiveDateTime,
+    pub heartbeat_at: NaiveDateTime,
+}
+
+#[derive(QueryableByName)]
+pub struct AdvisoryLock {
+    #[diesel(sql_type = Bool)]
+    pub acquired: bool,
+}
+
+#[derive(Serialize)]
+pub struct LeaderStatus {
+    pub name: String,
+    // Instance which served the request
+    pub instance_id: String,
+    pub is_leader: bool,
+    // Leader is considered dead if it hasn't sent heartbeat for a while
+    pub leader_alive: bool,
+    pub leader: Option<ServiceLeader>,
+}
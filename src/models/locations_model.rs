@@ -0,0 +1,49 @@
+use crate::schema::schema::courier_locations;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Queryable, Serialize)]
+#[diesel(table_name = courier_locations)]
+pub struct CourierLocation {
+    pub courier_uuid: Uuid,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub recorded_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = courier_locations)]
+pub struct UpsertCourierLocation {
+    pub courier_uuid: Uuid,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub recorded_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Validate, Clone)]
+pub struct LocationPing {
+    #[validate(range(min = -90.0, max = 90.0, message = "Latitude must be between -90 and 90"))]
+    pub latitude: f64,
+
+    #[validate(range(
+        min = -180.0,
+        max = 180.0,
+        message = "Longitude must be between -180 and 180"
+    ))]
+    pub longitude: f64,
+
+    // Time of the measurement on the device, current time if omitted
+    pub recorded_at: Option<NaiveDateTime>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct LocationPingsBatch {
+    #[validate(length(min = 1, max = 500, message = "Batch must contain from 1 to 500 pings"))]
+    #[validate]
+    pub pings: Vec<LocationPing>,
+}
@@ -1,3 +1,16 @@
+pub mod analytics_model;
+pub mod assignments_model;
 pub mod couriers_model;
+pub mod documents_model;
+pub mod leaders_model;
+pub mod locations_model;
+pub mod offers_model;
+pub mod outbox_model;
 pub mod queue_model;
+pub mod ratings_model;
+pub mod schedules_model;
+pub mod shifts_model;
+pub mod stats_model;
 pub mod users_model;
+pub mod vehicles_model;
+pub mod zones_model;
@@ -0,0 +1,41 @@
+use crate::schema::schema::courier_offers;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Queryable, Serialize, Clone)]
+#[diesel(table_name = courier_offers)]
+pub struct CourierOffer {
+    pub id: i64,
+    pub queue_id: i64,
+    pub user_uuid: Uuid,
+    pub courier_uuid: Uuid,
+    pub status: String,
+    pub expires_at: NaiveDateTime,
+    pub responded_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = courier_offers)]
+pub struct CreateOffer {
+    pub queue_id: i64,
+    pub user_uuid: Uuid,
+    pub courier_uuid: Uuid,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = courier_offers)]
+pub struct UpdateOffer {
+    pub status: String,
+    pub responded_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize)]
+pub struct CourierOffersInfo {
+    pub offers: Vec<CourierOffer>,
+    pub acceptance_rate: f64,
+}
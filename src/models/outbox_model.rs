@@ -0,0 +1,118 @@
+use crate::models::analytics_model::AnalyticsEvent;
+use crate::schema::schema::outbox_messages;
+use crate::utils::validators::validate_outbox_status;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+use validator::Validate;
+
+// Notification for Orders or Analytics service, kept in outbox untill it's delivered
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Notification {
+    CourierFound {
+        user_uuid: Uuid,
+        courier_uuid: Uuid,
+        courier_rating: f64,
+        assignment_id: i64,
+    },
+    SearchExpired {
+        user_uuid: Uuid,
+    },
+    SearchCancelled {
+        user_uuid: Uuid,
+        queue_id: i64,
+    },
+    CourierReplaced {
+        user_uuid: Uuid,
+        courier_uuid: Uuid,
+        assignment_id: i64,
+        queue_id: i64,
+    },
+    // Sent to Analytics service
+    Registered {
+        uuid: Uuid,
+        role: String,
+        created_at: NaiveDateTime,
+    },
+    AnalyticsEvent(AnalyticsEvent),
+}
+
+impl Notification {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Notification::CourierFound { .. } => "COURIER_FOUND",
+            Notification::SearchExpired { .. } => "SEARCH_EXPIRED",
+            Notification::SearchCancelled { .. } => "SEARCH_CANCELLED",
+            Notification::CourierReplaced { .. } => "COURIER_REPLACED",
+            Notification::Registered { .. } => "REGISTERED",
+            Notification::AnalyticsEvent(_) => "ANALYTICS_EVENT",
+        }
+    }
+}
+
+#[derive(Queryable, Serialize)]
+#[diesel(table_name = outbox_messages)]
+pub struct OutboxMessage {
+    pub id: i64,
+    pub kind: String,
+    pub payload: Value,
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    pub delivered_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = outbox_messages)]
+pub struct CreateOutboxMessage {
+    pub kind: String,
+    pub payload: Value,
+}
+
+// Result of delivery attempt, error of a successful delivery is cleared
+#[derive(AsChangeset)]
+#[diesel(table_name = outbox_messages, treat_none_as_null = true)]
+pub struct UpdateOutboxMessage {
+    pub status: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    pub delivered_at: Option<NaiveDateTime>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct OutboxMessagesQuery {
+    #[validate(custom(
+        function = "validate_outbox_status",
+        message = "Must contain PENDING, DISPATCHING, DELIVERED or DEAD."
+    ))]
+    pub status: Option<String>,
+    #[serde(default = "default_page")]
+    #[validate(range(min = 1, max = 10000))]
+    pub page: i64,
+    #[serde(default = "default_page_limit")]
+    #[validate(range(min = 1, max = 100))]
+    pub limit: i64,
+}
+
+fn default_page() -> i64 {
+    1
+}
+
+fn default_page_limit() -> i64 {
+    20
+}
+
+#[derive(Serialize)]
+pub struct OutboxMessagesPage {
+    pub messages: Vec<OutboxMessage>,
+    pub total: i64,
+    pub page: i64,
+    pub limit: i64,
+}
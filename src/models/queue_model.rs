@@ -1,9 +1,13 @@
+use crate::models::vehicles_model::OrderSize;
 use crate::schema::schema::users_queue;
-use chrono::NaiveDateTime;
+use chrono::{Duration, NaiveDateTime};
 use diesel::prelude::*;
+use serde::Serialize;
 use uuid::Uuid;
 
-#[derive(Queryable)]
+pub const MAX_QUEUE_PRIORITY: i32 = 10;
+
+#[derive(Queryable, Serialize)]
 #[diesel(table_name = users_queue)]
 pub struct UserQueueInfo {
     pub id: i64,
@@ -11,12 +15,44 @@ pub struct UserQueueInfo {
     pub status: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub pickup_latitude: Option<f64>,
+    pub pickup_longitude: Option<f64>,
+    pub order_weight_kg: Option<f64>,
+    pub order_volume_liters: Option<f64>,
+    pub zone_id: Option<i64>,
+    pub priority: i32,
+}
+
+impl UserQueueInfo {
+    pub fn pickup_point(&self) -> Option<(f64, f64)> {
+        self.pickup_latitude.zip(self.pickup_longitude)
+    }
+
+    // Every priority level moves position ahead as if it had waited `priority_step`
+    // seconds longer, so long waiting positions of low priority can't starve
+    pub fn queue_key(&self, priority_step: i32) -> (NaiveDateTime, i64) {
+        let head_start = Duration::seconds(i64::from(self.priority) * i64::from(priority_step));
+        (self.created_at - head_start, self.id)
+    }
+
+    pub fn order_size(&self) -> OrderSize {
+        OrderSize {
+            weight_kg: self.order_weight_kg,
+            volume_liters: self.order_volume_liters,
+        }
+    }
 }
 
 #[derive(Insertable)]
 #[diesel(table_name = users_queue)]
 pub struct AddUserToQueue {
     pub user_uuid: Uuid,
+    pub pickup_latitude: Option<f64>,
+    pub pickup_longitude: Option<f64>,
+    pub order_weight_kg: Option<f64>,
+    pub order_volume_liters: Option<f64>,
+    pub zone_id: Option<i64>,
+    pub priority: i32,
 }
 
 #[derive(AsChangeset)]
@@ -25,6 +61,23 @@ pub struct UpdateQueueStatus {
     pub status: String,
 }
 
+// State of user's latest search for courier.
+// Waiting time and position are known only while searching
+#[derive(Serialize, Clone)]
+pub struct CourierSearchStatus {
+    pub queue_id: i64,
+    pub status: String,
+    pub avg_waiting_time: i32,
+    pub queue_position: i32,
+    pub courier_uuid: Option<Uuid>,
+}
+
+impl CourierSearchStatus {
+    pub fn is_final(&self) -> bool {
+        self.status != "SEARCHING"
+    }
+}
+
 #[derive(Queryable)]
 #[diesel(table_name = users_queue)]
 pub struct LastAttemp {
@@ -0,0 +1,113 @@
+use crate::schema::schema::courier_ratings;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Queryable, Serialize)]
+#[diesel(table_name = courier_ratings)]
+pub struct CourierRating {
+    pub id: i64,
+    pub assignment_id: i64,
+    pub courier_uuid: Uuid,
+    pub rater_uuid: Uuid,
+    pub score: f64,
+    pub comment: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = courier_ratings)]
+pub struct CreateCourierRating {
+    pub assignment_id: i64,
+    pub courier_uuid: Uuid,
+    pub rater_uuid: Uuid,
+    pub score: f64,
+    pub comment: Option<String>,
+}
+
+#[derive(Queryable, Serialize)]
+pub struct CourierReview {
+    pub assignment_id: i64,
+    pub score: f64,
+    pub comment: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Queryable)]
+pub struct CourierRatingSummary {
+    pub courier_uuid: Uuid,
+    pub rating: f64,
+    pub ratings_count: i64,
+}
+
+#[derive(Serialize)]
+pub struct CourierRatingInfo {
+    pub courier_uuid: Uuid,
+    pub rating: f64,
+    pub ratings_count: i64,
+    pub recent_reviews: Vec<CourierReview>,
+}
+
+impl CourierRatingInfo {
+    pub fn new(summary: CourierRatingSummary, recent_reviews: Vec<CourierReview>) -> Self {
+        CourierRatingInfo {
+            courier_uuid: summary.courier_uuid,
+            rating: summary.rating,
+            ratings_count: summary.ratings_count,
+            recent_reviews,
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum RatingsSort {
+    #[default]
+    RatingDesc,
+    RatingAsc,
+}
+
+impl FromStr for RatingsSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" | "rating_desc" => Ok(RatingsSort::RatingDesc),
+            "rating_asc" => Ok(RatingsSort::RatingAsc),
+            _ => Err(format!(
+                "Unknown sort {s}, expected rating_desc or rating_asc"
+            )),
+        }
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct RatingsPageRequest {
+    #[serde(default = "default_page")]
+    #[validate(range(min = 1, max = 10000))]
+    pub page: i64,
+    #[serde(default = "default_page_limit")]
+    #[validate(range(min = 1, max = 100))]
+    pub limit: i64,
+    #[serde(default)]
+    pub sort: RatingsSort,
+}
+
+fn default_page() -> i64 {
+    1
+}
+
+fn default_page_limit() -> i64 {
+    20
+}
+
+#[derive(Serialize)]
+pub struct CourierRatingsPage {
+    pub couriers: Vec<CourierRatingInfo>,
+    pub total: i64,
+    pub page: i64,
+    pub limit: i64,
+}
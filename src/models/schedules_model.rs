@@ -0,0 +1,97 @@
+use crate::schema::schema::{courier_availability, planned_shifts};
+use chrono::{NaiveDateTime, NaiveTime};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Queryable, Serialize)]
+#[diesel(table_name = courier_availability)]
+pub struct AvailabilityWindow {
+    pub id: i64,
+    pub courier_uuid: Uuid,
+    pub day_of_week: i16,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = courier_availability)]
+pub struct CreateAvailabilityWindow {
+    pub courier_uuid: Uuid,
+    pub day_of_week: i16,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+}
+
+// Day of week is ISO 8601, 1 is Monday
+#[derive(Serialize, Deserialize, Validate)]
+pub struct AvailabilityWindowForm {
+    #[validate(range(min = 1, max = 7, message = "Day of week must be from 1 to 7"))]
+    pub day_of_week: i16,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct AvailabilityForm {
+    #[validate(length(max = 100, message = "Courier can have at most 100 windows"))]
+    #[validate]
+    pub windows: Vec<AvailabilityWindowForm>,
+}
+
+#[derive(Queryable, Serialize)]
+#[diesel(table_name = planned_shifts)]
+pub struct PlannedShift {
+    pub id: i64,
+    pub courier_uuid: Uuid,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    pub created_by: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = planned_shifts)]
+pub struct CreatePlannedShift {
+    pub courier_uuid: Uuid,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    pub created_by: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct PlannedShiftForm {
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+}
+
+// Period of planned shifts, upcoming week by default
+#[derive(Deserialize)]
+pub struct PlannedShiftsQuery {
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+}
+
+// Forecast starts from the current hour by default
+#[derive(Deserialize, Validate)]
+pub struct CapacityForecastQuery {
+    pub from: Option<NaiveDateTime>,
+
+    #[validate(range(min = 1, max = 168, message = "Hours must be from 1 to 168"))]
+    pub hours: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct HourForecast {
+    pub hour_start: NaiveDateTime,
+    // Average number of queue positions created at this hour of the same weekday
+    pub expected_orders: f64,
+    pub required_courier_hours: f64,
+    pub planned_courier_hours: f64,
+    // Couriers whose weekly availability covers the whole hour
+    pub available_couriers: i64,
+    pub shortage_courier_hours: f64,
+}
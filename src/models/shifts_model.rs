@@ -0,0 +1,90 @@
+use crate::schema::schema::courier_shifts;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Queryable, Serialize)]
+#[diesel(table_name = courier_shifts)]
+pub struct CourierShift {
+    pub id: i64,
+    pub courier_uuid: Uuid,
+    pub status: String,
+    pub started_at: NaiveDateTime,
+    pub paused_at: Option<NaiveDateTime>,
+    pub paused_seconds: i64,
+    pub finished_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl CourierShift {
+    // Time spent on shift excluding pauses.
+    // Unfinished shifts are counted untill `now`
+    pub fn worked_seconds(&self, now: NaiveDateTime) -> i64 {
+        let finished_at = self.finished_at.unwrap_or(now);
+        let current_pause = match self.paused_at {
+            Some(paused_at) => (finished_at - paused_at).num_seconds(),
+            None => 0,
+        };
+        (finished_at - self.started_at).num_seconds() - self.paused_seconds - current_pause
+    }
+
+    // Worked time falling into [from, to]. Only the current pause is known exactly,
+    // earlier ones are treated as spread evenly over the shift
+    pub fn worked_seconds_between(
+        &self,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        now: NaiveDateTime,
+    ) -> i64 {
+        let active_until = self.paused_at.or(self.finished_at).unwrap_or(now);
+        let span = (active_until - self.started_at).num_seconds();
+        if span <= 0 {
+            return 0;
+        }
+        let overlap = (active_until.min(to) - self.started_at.max(from))
+            .num_seconds()
+            .max(0);
+        (span - self.paused_seconds).max(0) * overlap / span
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = courier_shifts)]
+pub struct CreateShift {
+    pub courier_uuid: Uuid,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = courier_shifts)]
+pub struct UpdateShift {
+    pub status: Option<String>,
+    pub paused_at: Option<Option<NaiveDateTime>>,
+    pub paused_seconds: Option<i64>,
+    pub finished_at: Option<Option<NaiveDateTime>>,
+}
+
+#[derive(Serialize)]
+pub struct ShiftInfo {
+    pub id: i64,
+    pub status: String,
+    pub started_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+    pub paused_seconds: i64,
+    pub worked_seconds: i64,
+}
+
+impl ShiftInfo {
+    pub fn new(shift: CourierShift, now: NaiveDateTime) -> Self {
+        let worked_seconds = shift.worked_seconds(now);
+        ShiftInfo {
+            id: shift.id,
+            status: shift.status,
+            started_at: shift.started_at,
+            finished_at: shift.finished_at,
+            paused_seconds: shift.paused_seconds,
+            worked_seconds,
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// Period of statistics, last 30 days by default
+#[derive(Deserialize)]
+pub struct StatsPeriodQuery {
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize)]
+pub struct CourierStats {
+    pub courier_uuid: Uuid,
+    pub from: NaiveDateTime,
+    pub to: NaiveDateTime,
+    pub deliveries_completed: i64,
+    pub deliveries_cancelled: i64,
+    pub acceptance_rate: f64,
+    pub average_delivery_seconds: Option<i64>,
+    // Shifts started in the period, pauses excluded
+    pub online_hours: f64,
+    pub delivery_fee: f64,
+    pub earnings: f64,
+    pub rating: f64,
+    pub ratings_count: i64,
+    pub period_average_score: Option<f64>,
+}
@@ -0,0 +1,115 @@
+use crate::schema::schema::courier_vehicles;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+// Couriers without vehicle profile take one order of unknown size at a time
+pub const DEFAULT_MAX_CONCURRENT_DELIVERIES: i32 = 1;
+
+#[derive(Queryable, Serialize)]
+#[diesel(table_name = courier_vehicles)]
+pub struct CourierVehicle {
+    pub courier_uuid: Uuid,
+    pub vehicle_type: String,
+    pub max_weight_kg: f64,
+    pub max_volume_liters: f64,
+    pub max_concurrent_deliveries: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = courier_vehicles)]
+pub struct UpsertCourierVehicle {
+    pub courier_uuid: Uuid,
+    pub vehicle_type: String,
+    pub max_weight_kg: f64,
+    pub max_volume_liters: f64,
+    pub max_concurrent_deliveries: i32,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum VehicleType {
+    Foot,
+    Bike,
+    Scooter,
+    Car,
+}
+
+impl VehicleType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VehicleType::Foot => "FOOT",
+            VehicleType::Bike => "BIKE",
+            VehicleType::Scooter => "SCOOTER",
+            VehicleType::Car => "CAR",
+        }
+    }
+}
+
+#[derive(Deserialize, Validate)]
+pub struct VehicleProfile {
+    pub vehicle_type: VehicleType,
+
+    #[validate(range(
+        min = 0.1,
+        max = 2000.0,
+        message = "Max weight must be between 0.1 and 2000 kg"
+    ))]
+    pub max_weight_kg: f64,
+
+    #[validate(range(
+        min = 0.1,
+        max = 10000.0,
+        message = "Max volume must be between 0.1 and 10000 liters"
+    ))]
+    pub max_volume_liters: f64,
+
+    #[validate(range(
+        min = 1,
+        max = 10,
+        message = "Max concurrent deliveries must be between 1 and 10"
+    ))]
+    pub max_concurrent_deliveries: i32,
+}
+
+// Active deliveries of the courier and their total size.
+// Orders of unknown size don't take any weight or volume
+#[derive(Queryable)]
+pub struct CourierLoad {
+    pub courier_uuid: Uuid,
+    pub deliveries: i64,
+    pub weight_kg: Option<f64>,
+    pub volume_liters: Option<f64>,
+}
+
+pub struct OrderSize {
+    pub weight_kg: Option<f64>,
+    pub volume_liters: Option<f64>,
+}
+
+impl OrderSize {
+    pub fn is_unknown(&self) -> bool {
+        self.weight_kg.is_none() && self.volume_liters.is_none()
+    }
+}
+
+// Whether courier can take one more order of the given size in addition to his current load
+pub fn has_spare_capacity(
+    vehicle: Option<&CourierVehicle>,
+    load: Option<&CourierLoad>,
+    order: &OrderSize,
+) -> bool {
+    let deliveries = load.map_or(0, |load| load.deliveries);
+    let Some(vehicle) = vehicle else {
+        return order.is_unknown() && deliveries < DEFAULT_MAX_CONCURRENT_DELIVERIES.into();
+    };
+    let loaded_weight = load.and_then(|load| load.weight_kg).unwrap_or_default();
+    let loaded_volume = load.and_then(|load| load.volume_liters).unwrap_or_default();
+    deliveries < vehicle.max_concurrent_deliveries.into()
+        && loaded_weight + order.weight_kg.unwrap_or_default() <= vehicle.max_weight_kg
+        && loaded_volume + order.volume_liters.unwrap_or_default() <= vehicle.max_volume_liters
+}
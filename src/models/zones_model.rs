@@ -0,0 +1,110 @@
+use crate::schema::schema::{courier_zones, zone_neighbours, zones};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Clone, Copy)]
+pub struct GeoPoint {
+    #[validate(range(min = -90.0, max = 90.0, message = "Latitude must be between -90 and 90"))]
+    pub latitude: f64,
+
+    #[validate(range(
+        min = -180.0,
+        max = 180.0,
+        message = "Longitude must be between -180 and 180"
+    ))]
+    pub longitude: f64,
+}
+
+#[derive(Queryable)]
+#[diesel(table_name = zones)]
+pub struct Zone {
+    pub id: i64,
+    pub name: String,
+    pub polygon: serde_json::Value,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Zone {
+    // Vertices as (latitude, longitude), empty if stored polygon is malformed
+    pub fn vertices(&self) -> Vec<(f64, f64)> {
+        serde_json::from_value::<Vec<GeoPoint>>(self.polygon.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|point| (point.latitude, point.longitude))
+            .collect()
+    }
+}
+
+#[derive(Serialize)]
+pub struct ZoneInfo {
+    pub id: i64,
+    pub name: String,
+    pub polygon: serde_json::Value,
+    pub neighbours: Vec<i64>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl ZoneInfo {
+    pub fn new(zone: Zone, neighbours: Vec<i64>) -> Self {
+        ZoneInfo {
+            id: zone.id,
+            name: zone.name,
+            polygon: zone.polygon,
+            neighbours,
+            created_at: zone.created_at,
+            updated_at: zone.updated_at,
+        }
+    }
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = zones)]
+pub struct UpsertZone {
+    pub name: String,
+    pub polygon: serde_json::Value,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ZoneForm {
+    #[validate(length(min = 1, max = 100, message = "Name must be from 1 to 100 characters"))]
+    pub name: String,
+
+    #[validate(length(
+        min = 3,
+        max = 1000,
+        message = "Polygon must have from 3 to 1000 points"
+    ))]
+    #[validate]
+    pub polygon: Vec<GeoPoint>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = zone_neighbours)]
+pub struct CreateZoneNeighbour {
+    pub zone_id: i64,
+    pub neighbour_id: i64,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ZoneNeighboursForm {
+    #[validate(length(max = 100, message = "Zone can have at most 100 neighbours"))]
+    pub neighbours: Vec<i64>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = courier_zones)]
+pub struct CreateCourierZone {
+    pub courier_uuid: Uuid,
+    pub zone_id: i64,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct CourierZonesForm {
+    #[validate(length(max = 100, message = "Courier can have at most 100 zones"))]
+    pub zones: Vec<i64>,
+}
@@ -0,0 +1,166 @@
+use crate::models::assignments_model::*;
+use crate::models::vehicles_model::CourierLoad;
+use crate::resources::postgres::DbConn;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+pub const ACTIVE_STATUSES: [&str; 2] = ["ASSIGNED", "PICKED_UP"];
+
+pub async fn create_assignment(
+    db_conn: &mut DbConn<'_>,
+    assignment: CreateAssignment,
+) -> Result<Assignment, Error> {
+    use crate::schema::schema::assignments::dsl::*;
+    diesel::insert_into(assignments)
+        .values(assignment)
+        .get_result(db_conn)
+        .await
+}
+
+pub async fn select_assignment(
+    db_conn: &mut DbConn<'_>,
+    assignment_id: i64,
+) -> Result<Option<Assignment>, Error> {
+    use crate::schema::schema::assignments::dsl::*;
+    assignments
+        .find(assignment_id)
+        .get_result::<Assignment>(db_conn)
+        .await
+        .optional()
+}
+
+pub async fn select_queue_assignment(
+    db_conn: &mut DbConn<'_>,
+    position_id: i64,
+) -> Result<Option<Assignment>, Error> {
+    use crate::schema::schema::assignments::dsl::*;
+    assignments
+        .filter(queue_id.eq(position_id))
+        .order(created_at.desc())
+        .first::<Assignment>(db_conn)
+        .await
+        .optional()
+}
+
+// Locks assignment row untill the end of current transaction
+pub async fn select_assignment_for_update(
+    db_conn: &mut DbConn<'_>,
+    assignment_id: i64,
+) -> Result<Option<Assignment>, Error> {
+    use crate::schema::schema::assignments::dsl::*;
+    assignments
+        .find(assignment_id)
+        .for_update()
+        .get_result::<Assignment>(db_conn)
+        .await
+        .optional()
+}
+
+pub async fn select_active_courier_assignments(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<Vec<Assignment>, Error> {
+    use crate::schema::schema::assignments::dsl::*;
+    assignments
+        .filter(courier_uuid.eq(courier).and(status.eq_any(ACTIVE_STATUSES)))
+        .order(assigned_at.asc())
+        .load::<Assignment>(db_conn)
+        .await
+}
+
+pub async fn count_active_courier_assignments(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<i64, Error> {
+    use crate::schema::schema::assignments::dsl::*;
+    assignments
+        .filter(courier_uuid.eq(courier).and(status.eq_any(ACTIVE_STATUSES)))
+        .count()
+        .get_result(db_conn)
+        .await
+}
+
+// Time of the latest assignment of every given courier who has ever been assigned
+pub async fn select_last_assignment_times(
+    db_conn: &mut DbConn<'_>,
+    couriers: &[Uuid],
+) -> Result<Vec<(Uuid, Option<NaiveDateTime>)>, Error> {
+    use crate::schema::schema::assignments::dsl::*;
+    assignments
+        .filter(courier_uuid.eq_any(couriers))
+        .group_by(courier_uuid)
+        .select((courier_uuid, diesel::dsl::max(assigned_at)))
+        .load::<(Uuid, Option<NaiveDateTime>)>(db_conn)
+        .await
+}
+
+// Number and total size of active deliveries of every given courier who has any
+pub async fn select_active_loads(
+    db_conn: &mut DbConn<'_>,
+    couriers: &[Uuid],
+) -> Result<Vec<CourierLoad>, Error> {
+    use crate::schema::schema::assignments::dsl::*;
+    use crate::schema::schema::users_queue;
+    assignments
+        .left_join(users_queue::table)
+        .filter(
+            courier_uuid
+                .eq_any(couriers)
+                .and(status.eq_any(ACTIVE_STATUSES)),
+        )
+        .group_by(courier_uuid)
+        .select((
+            courier_uuid,
+            diesel::dsl::count_star(),
+            diesel::dsl::sum(users_queue::order_weight_kg.nullable()),
+            diesel::dsl::sum(users_queue::order_volume_liters.nullable()),
+        ))
+        .load::<CourierLoad>(db_conn)
+        .await
+}
+
+pub async fn update_assignment(
+    db_conn: &mut DbConn<'_>,
+    assignment_id: i64,
+    new_info: UpdateAssignment,
+) -> Result<Assignment, Error> {
+    use crate::schema::schema::assignments::dsl::*;
+    diesel::update(assignments.find(assignment_id))
+        .set(new_info)
+        .get_result(db_conn)
+        .await
+}
+
+// Assignment and delivery times of orders delivered in the period
+pub async fn select_delivered_between(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<Vec<(NaiveDateTime, Option<NaiveDateTime>)>, Error> {
+    use crate::schema::schema::assignments::dsl::*;
+    assignments
+        .filter(courier_uuid.eq(courier).and(status.eq("DELIVERED")))
+        .filter(delivered_at.ge(from).and(delivered_at.lt(to)))
+        .select((assigned_at, delivered_at))
+        .load::<(NaiveDateTime, Option<NaiveDateTime>)>(db_conn)
+        .await
+}
+
+pub async fn count_cancelled_between(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<i64, Error> {
+    use crate::schema::schema::assignments::dsl::*;
+    assignments
+        .filter(courier_uuid.eq(courier).and(status.eq("CANCELLED")))
+        .filter(cancelled_at.ge(from).and(cancelled_at.lt(to)))
+        .count()
+        .get_result(db_conn)
+        .await
+}
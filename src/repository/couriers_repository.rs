@@ -1,5 +1,6 @@
 use crate::models::couriers_model::*;
 use crate::resources::postgres::DbConn;
+use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use diesel::result::Error;
 use diesel_async::RunQueryDsl;
@@ -17,16 +18,35 @@ pub async fn create_courier(
         .await
 }
 
-pub async fn find_free_courier(db_conn: &mut DbConn<'_>) -> Result<Option<CourierInfo>, Error> {
+// Approved couriers on shift with spare capacity, the ones who became free earlier go first.
+// `free_since` is set by trigger when courier becomes available
+pub async fn select_free_couriers(
+    db_conn: &mut DbConn<'_>,
+    excluded_couriers: &[Uuid],
+) -> Result<Vec<CourierInfo>, Error> {
     use crate::schema::schema::couriers::dsl::*;
 
     couriers
-        .filter(is_free.eq(true))
+        .filter(is_free.eq(true).and(is_on_shift.eq(true)))
+        .filter(onboarding_status.eq("APPROVED"))
+        .filter(user_uuid.ne_all(excluded_couriers))
+        .order(free_since.asc())
         .select((user_uuid, is_free, rating))
-        .limit(1)
-        .get_result(db_conn)
+        .load::<CourierInfo>(db_conn)
+        .await
+}
+
+// Atomic claim of courier for an offer, returns 0 if courier has been
+// taken by another distributor or isn't available anymore
+pub async fn claim_free_courier(db_conn: &mut DbConn<'_>, uuid: Uuid) -> Result<usize, Error> {
+    use crate::schema::schema::couriers::dsl::*;
+
+    diesel::update(couriers.filter(user_uuid.eq(uuid)))
+        .filter(is_free.eq(true).and(is_on_shift.eq(true)))
+        .filter(onboarding_status.eq("APPROVED"))
+        .set(is_free.eq(false))
+        .execute(db_conn)
         .await
-        .optional()
 }
 
 pub async fn update_courier(
@@ -43,6 +63,20 @@ pub async fn update_courier(
         .await
 }
 
+pub async fn update_courier_rating(
+    db_conn: &mut DbConn<'_>,
+    uuid: Uuid,
+    new_rating: UpdateCourierRating,
+) -> Result<usize, Error> {
+    use crate::schema::schema::couriers::dsl::*;
+
+    diesel::update(couriers)
+        .filter(user_uuid.eq(uuid))
+        .set(new_rating)
+        .execute(db_conn)
+        .await
+}
+
 pub async fn select_courier(db_conn: &mut DbConn<'_>, uuid: Uuid) -> Result<CourierInfo, Error> {
     use crate::schema::schema::couriers::dsl::*;
     couriers
@@ -52,6 +86,21 @@ pub async fn select_courier(db_conn: &mut DbConn<'_>, uuid: Uuid) -> Result<Cour
         .await
 }
 
+// Locks courier row untill the end of current transaction
+pub async fn select_courier_for_update(
+    db_conn: &mut DbConn<'_>,
+    uuid: Uuid,
+) -> Result<Option<CourierInfo>, Error> {
+    use crate::schema::schema::couriers::dsl::*;
+    couriers
+        .filter(user_uuid.eq(uuid))
+        .select((user_uuid, is_free, rating))
+        .for_update()
+        .get_result::<CourierInfo>(db_conn)
+        .await
+        .optional()
+}
+
 pub async fn select_all_couriers(db_conn: &mut DbConn<'_>) -> Result<Vec<CourierInfo>, Error> {
     use crate::schema::schema::couriers::dsl::*;
     couriers
@@ -59,3 +108,107 @@ pub async fn select_all_couriers(db_conn: &mut DbConn<'_>) -> Result<Vec<Courier
         .load::<CourierInfo>(db_conn)
         .await
 }
+
+pub async fn select_courier_onboarding(
+    db_conn: &mut DbConn<'_>,
+    uuid: Uuid,
+) -> Result<Option<CourierOnboarding>, Error> {
+    use crate::schema::schema::couriers::dsl::*;
+    couriers
+        .filter(user_uuid.eq(uuid))
+        .select((user_uuid, onboarding_status, rejection_reason, updated_at))
+        .get_result::<CourierOnboarding>(db_conn)
+        .await
+        .optional()
+}
+
+// Locks courier row, so concurrent reviews and uploads can't skip a state
+pub async fn select_courier_onboarding_for_update(
+    db_conn: &mut DbConn<'_>,
+    uuid: Uuid,
+) -> Result<Option<CourierOnboarding>, Error> {
+    use crate::schema::schema::couriers::dsl::*;
+    couriers
+        .filter(user_uuid.eq(uuid))
+        .select((user_uuid, onboarding_status, rejection_reason, updated_at))
+        .for_update()
+        .get_result::<CourierOnboarding>(db_conn)
+        .await
+        .optional()
+}
+
+pub async fn select_couriers_by_onboarding_status(
+    db_conn: &mut DbConn<'_>,
+    status: &str,
+) -> Result<Vec<CourierOnboarding>, Error> {
+    use crate::schema::schema::couriers::dsl::*;
+    couriers
+        .filter(onboarding_status.eq(status))
+        .order(updated_at.asc())
+        .select((user_uuid, onboarding_status, rejection_reason, updated_at))
+        .load::<CourierOnboarding>(db_conn)
+        .await
+}
+
+pub async fn update_onboarding_status(
+    db_conn: &mut DbConn<'_>,
+    uuid: Uuid,
+    new_status: UpdateOnboardingStatus,
+) -> Result<usize, Error> {
+    use crate::schema::schema::couriers::dsl::*;
+
+    diesel::update(couriers)
+        .filter(user_uuid.eq(uuid))
+        .set(new_status)
+        .execute(db_conn)
+        .await
+}
+
+pub async fn touch_courier(
+    db_conn: &mut DbConn<'_>,
+    uuid: Uuid,
+    seen_at: NaiveDateTime,
+) -> Result<Option<CourierHeartbeat>, Error> {
+    use crate::schema::schema::couriers::dsl::*;
+
+    diesel::update(couriers)
+        .filter(user_uuid.eq(uuid))
+        .set(last_seen_at.eq(seen_at))
+        .returning((last_seen_at, is_on_shift))
+        .get_result::<CourierHeartbeat>(db_conn)
+        .await
+        .optional()
+}
+
+// Couriers on shift who haven't sent anything since the given moment
+pub async fn select_silent_couriers(
+    db_conn: &mut DbConn<'_>,
+    since: NaiveDateTime,
+) -> Result<Vec<Uuid>, Error> {
+    use crate::schema::schema::couriers::dsl::*;
+    couriers
+        .filter(is_on_shift.eq(true))
+        .filter(last_seen_at.is_null().or(last_seen_at.lt(since)))
+        .select(user_uuid)
+        .load::<Uuid>(db_conn)
+        .await
+}
+
+// Locks courier row untill the end of current transaction if he is still silent,
+// so the heartbeat sent meanwhile isn't overwritten by taking him offline
+pub async fn lock_silent_courier(
+    db_conn: &mut DbConn<'_>,
+    uuid: Uuid,
+    since: NaiveDateTime,
+) -> Result<Option<Uuid>, Error> {
+    use crate::schema::schema::couriers::dsl::*;
+    couriers
+        .filter(user_uuid.eq(uuid))
+        .filter(is_on_shift.eq(true))
+        .filter(last_seen_at.is_null().or(last_seen_at.lt(since)))
+        .select(user_uuid)
+        .for_update()
+        .get_result::<Uuid>(db_conn)
+        .await
+        .optional()
+}
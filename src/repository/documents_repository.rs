@@ -0,0 +1,58 @@
+use crate::models::documents_model::*;
+use crate::resources::postgres::DbConn;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+pub async fn upsert_document(
+    db_conn: &mut DbConn<'_>,
+    document: UpsertCourierDocument,
+) -> Result<CourierDocument, Error> {
+    use crate::schema::schema::courier_documents::dsl::*;
+    diesel::insert_into(courier_documents)
+        .values(&document)
+        .on_conflict((courier_uuid, document_type))
+        .do_update()
+        .set(&document)
+        .get_result(db_conn)
+        .await
+}
+
+pub async fn select_courier_document_by_type(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    doc_type: &str,
+) -> Result<Option<CourierDocument>, Error> {
+    use crate::schema::schema::courier_documents::dsl::*;
+    courier_documents
+        .filter(courier_uuid.eq(courier).and(document_type.eq(doc_type)))
+        .get_result::<CourierDocument>(db_conn)
+        .await
+        .optional()
+}
+
+pub async fn select_courier_document(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    document_id: i64,
+) -> Result<Option<CourierDocument>, Error> {
+    use crate::schema::schema::courier_documents::dsl::*;
+    courier_documents
+        .filter(id.eq(document_id).and(courier_uuid.eq(courier)))
+        .get_result::<CourierDocument>(db_conn)
+        .await
+        .optional()
+}
+
+pub async fn select_courier_documents(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<Vec<CourierDocument>, Error> {
+    use crate::schema::schema::courier_documents::dsl::*;
+    courier_documents
+        .filter(courier_uuid.eq(courier))
+        .order(document_type.asc())
+        .load::<CourierDocument>(db_conn)
+        .await
+}
@@ -0,0 +1,62 @@
+use crate::models::leaders_model::*;
+use crate::resources::postgres::DbConn;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::sql_types::BigInt;
+use diesel_async::RunQueryDsl;
+
+// Session level lock, it's held untill unlocked or untill the connection is closed
+pub async fn try_advisory_lock(db_conn: &mut DbConn<'_>, key: i64) -> Result<bool, Error> {
+    diesel::sql_query("SELECT pg_try_advisory_lock($1) AS acquired")
+        .bind::<BigInt, _>(key)
+        .get_result::<AdvisoryLock>(db_conn)
+        .await
+        .map(|lock| lock.acquired)
+}
+
+pub async fn advisory_unlock(db_conn: &mut DbConn<'_>, key: i64) -> Result<bool, Error> {
+    diesel::sql_query("SELECT pg_advisory_unlock($1) AS acquired")
+        .bind::<BigInt, _>(key)
+        .get_result::<AdvisoryLock>(db_conn)
+        .await
+        .map(|lock| lock.acquired)
+}
+
+pub async fn upsert_leader(db_conn: &mut DbConn<'_>, leader: ServiceLeader) -> Result<(), Error> {
+    use crate::schema::schema::service_leaders::dsl::*;
+    diesel::insert_into(service_leaders)
+        .values(&leader)
+        .on_conflict(name)
+        .do_update()
+        .set(&leader)
+        .execute(db_conn)
+        .await?;
+    Ok(())
+}
+
+pub async fn touch_leader(
+    db_conn: &mut DbConn<'_>,
+    service: &str,
+    instance: &str,
+    now: NaiveDateTime,
+) -> Result<usize, Error> {
+    use crate::schema::schema::service_leaders::dsl::*;
+    diesel::update(service_leaders.find(service))
+        .filter(instance_id.eq(instance))
+        .set(heartbeat_at.eq(now))
+        .execute(db_conn)
+        .await
+}
+
+pub async fn select_leader(
+    db_conn: &mut DbConn<'_>,
+    service: &str,
+) -> Result<Option<ServiceLeader>, Error> {
+    use crate::schema::schema::service_leaders::dsl::*;
+    service_leaders
+        .find(service)
+        .get_result::<ServiceLeader>(db_conn)
+        .await
+        .optional()
+}
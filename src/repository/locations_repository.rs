@@ -0,0 +1,46 @@
+use crate::models::locations_model::*;
+use crate::resources::postgres::DbConn;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+pub async fn upsert_location(
+    db_conn: &mut DbConn<'_>,
+    location: UpsertCourierLocation,
+) -> Result<CourierLocation, Error> {
+    use crate::schema::schema::courier_locations::dsl::*;
+    diesel::insert_into(courier_locations)
+        .values(&location)
+        .on_conflict(courier_uuid)
+        .do_update()
+        .set(&location)
+        .get_result(db_conn)
+        .await
+}
+
+pub async fn select_location(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<Option<CourierLocation>, Error> {
+    use crate::schema::schema::courier_locations::dsl::*;
+    courier_locations
+        .find(courier)
+        .get_result::<CourierLocation>(db_conn)
+        .await
+        .optional()
+}
+
+// Locations of given couriers reported not earlier than `since`
+pub async fn select_fresh_locations(
+    db_conn: &mut DbConn<'_>,
+    couriers: &[Uuid],
+    since: NaiveDateTime,
+) -> Result<Vec<CourierLocation>, Error> {
+    use crate::schema::schema::courier_locations::dsl::*;
+    courier_locations
+        .filter(courier_uuid.eq_any(couriers).and(recorded_at.ge(since)))
+        .load::<CourierLocation>(db_conn)
+        .await
+}
@@ -1,3 +1,14 @@
+pub mod assignments_repository;
 pub mod couriers_repository;
+pub mod documents_repository;
+pub mod leaders_repository;
+pub mod locations_repository;
+pub mod offers_repository;
+pub mod outbox_repository;
 pub mod queue_repository;
+pub mod ratings_repository;
+pub mod schedules_repository;
+pub mod shifts_repository;
 pub mod users_repository;
+pub mod vehicles_repository;
+pub mod zones_repository;
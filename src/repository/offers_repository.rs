@@ -0,0 +1,122 @@
+use crate::models::offers_model::*;
+use crate::resources::postgres::DbConn;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+pub async fn create_offer(
+    db_conn: &mut DbConn<'_>,
+    offer: CreateOffer,
+) -> Result<CourierOffer, Error> {
+    use crate::schema::schema::courier_offers::dsl::*;
+    diesel::insert_into(courier_offers)
+        .values(offer)
+        .get_result(db_conn)
+        .await
+}
+
+// Locks offer row untill the end of current transaction
+pub async fn select_offer_for_update(
+    db_conn: &mut DbConn<'_>,
+    offer_id: i64,
+) -> Result<Option<CourierOffer>, Error> {
+    use crate::schema::schema::courier_offers::dsl::*;
+    courier_offers
+        .find(offer_id)
+        .for_update()
+        .get_result::<CourierOffer>(db_conn)
+        .await
+        .optional()
+}
+
+pub async fn select_pending_queue_offer(
+    db_conn: &mut DbConn<'_>,
+    queue: i64,
+) -> Result<Option<CourierOffer>, Error> {
+    use crate::schema::schema::courier_offers::dsl::*;
+    courier_offers
+        .filter(queue_id.eq(queue).and(status.eq("PENDING")))
+        .get_result::<CourierOffer>(db_conn)
+        .await
+        .optional()
+}
+
+pub async fn select_pending_courier_offers(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<Vec<CourierOffer>, Error> {
+    use crate::schema::schema::courier_offers::dsl::*;
+    courier_offers
+        .filter(courier_uuid.eq(courier).and(status.eq("PENDING")))
+        .order(created_at.asc())
+        .load::<CourierOffer>(db_conn)
+        .await
+}
+
+pub async fn select_timed_out_offers(
+    db_conn: &mut DbConn<'_>,
+    current_time: NaiveDateTime,
+) -> Result<Vec<CourierOffer>, Error> {
+    use crate::schema::schema::courier_offers::dsl::*;
+    courier_offers
+        .filter(status.eq("PENDING").and(expires_at.lt(current_time)))
+        .load::<CourierOffer>(db_conn)
+        .await
+}
+
+// Couriers who have already been offered given position in queue
+pub async fn select_offered_couriers(
+    db_conn: &mut DbConn<'_>,
+    queue: i64,
+) -> Result<Vec<Uuid>, Error> {
+    use crate::schema::schema::courier_offers::dsl::*;
+    courier_offers
+        .filter(queue_id.eq(queue))
+        .select(courier_uuid)
+        .load::<Uuid>(db_conn)
+        .await
+}
+
+pub async fn count_courier_offers_by_status(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    offer_status: &str,
+) -> Result<i64, Error> {
+    use crate::schema::schema::courier_offers::dsl::*;
+    courier_offers
+        .filter(courier_uuid.eq(courier).and(status.eq(offer_status)))
+        .count()
+        .get_result(db_conn)
+        .await
+}
+
+pub async fn update_offer(
+    db_conn: &mut DbConn<'_>,
+    offer_id: i64,
+    new_info: UpdateOffer,
+) -> Result<CourierOffer, Error> {
+    use crate::schema::schema::courier_offers::dsl::*;
+    diesel::update(courier_offers.find(offer_id))
+        .set(new_info)
+        .get_result(db_conn)
+        .await
+}
+
+// Number of offers answered in the period grouped by their final status
+pub async fn count_courier_answered_offers_between(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<Vec<(String, i64)>, Error> {
+    use crate::schema::schema::courier_offers::dsl::*;
+    courier_offers
+        .filter(courier_uuid.eq(courier).and(status.ne("PENDING")))
+        .filter(responded_at.ge(from).and(responded_at.lt(to)))
+        .group_by(status)
+        .select((status, diesel::dsl::count_star()))
+        .load::<(String, i64)>(db_conn)
+        .await
+}
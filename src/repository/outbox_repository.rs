@@ -0,0 +1,134 @@
+use crate::models::outbox_model::*;
+use crate::resources::postgres::DbConn;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel_async::RunQueryDsl;
+
+pub async fn insert_outbox_message(
+    db_conn: &mut DbConn<'_>,
+    message: CreateOutboxMessage,
+) -> Result<OutboxMessage, Error> {
+    use crate::schema::schema::outbox_messages::dsl::*;
+    diesel::insert_into(outbox_messages)
+        .values(message)
+        .get_result::<OutboxMessage>(db_conn)
+        .await
+}
+
+// Messages due for delivery, locked untill the end of current transaction.
+// Messages whose lease has expired are due again, messages locked by another dispatcher are skipped
+pub async fn lock_due_messages(
+    db_conn: &mut DbConn<'_>,
+    now: NaiveDateTime,
+    batch_size: i64,
+) -> Result<Vec<OutboxMessage>, Error> {
+    use crate::schema::schema::outbox_messages::dsl::*;
+    outbox_messages
+        .filter(status.eq_any(["PENDING", "DISPATCHING"]))
+        .filter(next_attempt_at.le(now))
+        .order((next_attempt_at.asc(), id.asc()))
+        .limit(batch_size)
+        .for_update()
+        .skip_locked()
+        .load::<OutboxMessage>(db_conn)
+        .await
+}
+
+// Messages are leased to the dispatcher untill `lease_until`,
+// their `next_attempt_at` identifies the lease when outcome is recorded
+pub async fn claim_outbox_messages(
+    db_conn: &mut DbConn<'_>,
+    message_ids: &[i64],
+    lease_until: NaiveDateTime,
+) -> Result<Vec<OutboxMessage>, Error> {
+    use crate::schema::schema::outbox_messages::dsl::*;
+    diesel::update(outbox_messages.filter(id.eq_any(message_ids)))
+        .set((status.eq("DISPATCHING"), next_attempt_at.eq(lease_until)))
+        .get_results::<OutboxMessage>(db_conn)
+        .await
+}
+
+// Nothing is updated if the lease has expired and message was claimed once again
+pub async fn update_claimed_message(
+    db_conn: &mut DbConn<'_>,
+    message_id: i64,
+    lease_until: NaiveDateTime,
+    new_info: UpdateOutboxMessage,
+) -> Result<usize, Error> {
+    use crate::schema::schema::outbox_messages::dsl::*;
+    diesel::update(
+        outbox_messages
+            .find(message_id)
+            .filter(status.eq("DISPATCHING"))
+            .filter(next_attempt_at.eq(lease_until)),
+    )
+    .set(new_info)
+    .execute(db_conn)
+    .await
+}
+
+pub async fn select_outbox_messages(
+    db_conn: &mut DbConn<'_>,
+    message_status: Option<&str>,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<OutboxMessage>, Error> {
+    use crate::schema::schema::outbox_messages::dsl::*;
+    let mut query = outbox_messages.into_boxed();
+    if let Some(message_status) = message_status {
+        query = query.filter(status.eq(message_status));
+    }
+    query
+        .order((created_at.desc(), id.desc()))
+        .offset(offset)
+        .limit(limit)
+        .load::<OutboxMessage>(db_conn)
+        .await
+}
+
+pub async fn count_outbox_messages(
+    db_conn: &mut DbConn<'_>,
+    message_status: Option<&str>,
+) -> Result<i64, Error> {
+    use crate::schema::schema::outbox_messages::dsl::*;
+    let mut query = outbox_messages.into_boxed();
+    if let Some(message_status) = message_status {
+        query = query.filter(status.eq(message_status));
+    }
+    query.count().get_result::<i64>(db_conn).await
+}
+
+pub async fn select_outbox_message(
+    db_conn: &mut DbConn<'_>,
+    message_id: i64,
+) -> Result<Option<OutboxMessage>, Error> {
+    use crate::schema::schema::outbox_messages::dsl::*;
+    outbox_messages
+        .find(message_id)
+        .get_result::<OutboxMessage>(db_conn)
+        .await
+        .optional()
+}
+
+// Message is sent again from the first attempt, only delivered and dead ones are replayed
+pub async fn replay_outbox_message(
+    db_conn: &mut DbConn<'_>,
+    message_id: i64,
+    now: NaiveDateTime,
+) -> Result<Option<OutboxMessage>, Error> {
+    use crate::schema::schema::outbox_messages::dsl::*;
+    diesel::update(
+        outbox_messages
+            .find(message_id)
+            .filter(status.eq_any(["DELIVERED", "DEAD"])),
+    )
+    .set((
+        status.eq("PENDING"),
+        attempts.eq(0),
+        next_attempt_at.eq(now),
+    ))
+    .get_result::<OutboxMessage>(db_conn)
+    .await
+    .optional()
+}
@@ -1,5 +1,6 @@
 use crate::models::queue_model::*;
 use crate::resources::postgres::DbConn;
+use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use diesel::result::Error;
 use diesel_async::RunQueryDsl;
@@ -11,34 +12,72 @@ pub async fn select_unfinished_queue(
     use crate::schema::schema::users_queue::dsl::*;
     users_queue
         .filter(status.eq("SEARCHING"))
-        .select((id, user_uuid, status, created_at, updated_at))
+        .order((created_at.asc(), id.asc()))
+        .select((
+            id,
+            user_uuid,
+            status,
+            created_at,
+            updated_at,
+            pickup_latitude,
+            pickup_longitude,
+            order_weight_kg,
+            order_volume_liters,
+            zone_id,
+            priority,
+        ))
         .get_results::<UserQueueInfo>(db_conn)
         .await
 }
 
-pub async fn select_queue_info(
+pub async fn select_queue_position(
     db_conn: &mut DbConn<'_>,
-    uuid: Uuid,
-) -> Result<UserQueueInfo, Error> {
+    queue_id: i64,
+) -> Result<Option<UserQueueInfo>, Error> {
     use crate::schema::schema::users_queue::dsl::*;
     users_queue
-        .filter(user_uuid.eq(uuid))
-        .order(created_at.desc())
-        .limit(1)
-        .select((id, user_uuid, status, created_at, updated_at))
+        .find(queue_id)
+        .select((
+            id,
+            user_uuid,
+            status,
+            created_at,
+            updated_at,
+            pickup_latitude,
+            pickup_longitude,
+            order_weight_kg,
+            order_volume_liters,
+            zone_id,
+            priority,
+        ))
         .get_result::<UserQueueInfo>(db_conn)
         .await
+        .optional()
 }
 
-pub async fn select_queue_untill_position(
+pub async fn select_queue_info(
     db_conn: &mut DbConn<'_>,
-    queue_id: i64,
-) -> Result<Vec<UserQueueInfo>, Error> {
+    uuid: Uuid,
+) -> Result<UserQueueInfo, Error> {
     use crate::schema::schema::users_queue::dsl::*;
     users_queue
-        .filter(status.eq("SEARCHING").and(id.lt(queue_id)))
-        .select((id, user_uuid, status, created_at, updated_at))
-        .get_results::<UserQueueInfo>(db_conn)
+        .filter(user_uuid.eq(uuid))
+        .order(created_at.desc())
+        .limit(1)
+        .select((
+            id,
+            user_uuid,
+            status,
+            created_at,
+            updated_at,
+            pickup_latitude,
+            pickup_longitude,
+            order_weight_kg,
+            order_volume_liters,
+            zone_id,
+            priority,
+        ))
+        .get_result::<UserQueueInfo>(db_conn)
         .await
 }
 
@@ -50,7 +89,19 @@ pub async fn select_last_ten_completed_positions(
     users_queue
         .order(created_at.desc())
         .filter(status.eq("COMPLETED").and(id.le(queue_id)))
-        .select((id, user_uuid, status, created_at, updated_at))
+        .select((
+            id,
+            user_uuid,
+            status,
+            created_at,
+            updated_at,
+            pickup_latitude,
+            pickup_longitude,
+            order_weight_kg,
+            order_volume_liters,
+            zone_id,
+            priority,
+        ))
         .limit(10)
         .get_results::<UserQueueInfo>(db_conn)
         .await
@@ -59,11 +110,24 @@ pub async fn select_last_ten_completed_positions(
 pub async fn add_user_to_queue(
     db_conn: &mut DbConn<'_>,
     user: AddUserToQueue,
-) -> Result<usize, Error> {
+) -> Result<UserQueueInfo, Error> {
     use crate::schema::schema::users_queue::dsl::*;
     diesel::insert_into(users_queue)
         .values(user)
-        .execute(db_conn)
+        .returning((
+            id,
+            user_uuid,
+            status,
+            created_at,
+            updated_at,
+            pickup_latitude,
+            pickup_longitude,
+            order_weight_kg,
+            order_volume_liters,
+            zone_id,
+            priority,
+        ))
+        .get_result::<UserQueueInfo>(db_conn)
         .await
 }
 
@@ -79,6 +143,65 @@ pub async fn change_order_status(
         .await
 }
 
+// Position is changed only if it's still searching for courier,
+// returns 0 if it has been completed, cancelled or expired meanwhile
+pub async fn finish_searching_position(
+    db_conn: &mut DbConn<'_>,
+    queue_id: i64,
+    new_status: &str,
+) -> Result<usize, Error> {
+    use crate::schema::schema::users_queue::dsl::*;
+    diesel::update(users_queue.find(queue_id).filter(status.eq("SEARCHING")))
+        .set(status.eq(new_status))
+        .execute(db_conn)
+        .await
+}
+
+// Locks searching position untill the end of current transaction.
+// Position locked by another distributor is skipped instead of waited for
+pub async fn lock_searching_position(
+    db_conn: &mut DbConn<'_>,
+    queue_id: i64,
+) -> Result<Option<i64>, Error> {
+    use crate::schema::schema::users_queue::dsl::*;
+    users_queue
+        .find(queue_id)
+        .filter(status.eq("SEARCHING"))
+        .select(id)
+        .for_update()
+        .skip_locked()
+        .get_result::<i64>(db_conn)
+        .await
+        .optional()
+}
+
+pub async fn select_searching_position(
+    db_conn: &mut DbConn<'_>,
+    user: Uuid,
+) -> Result<Option<UserQueueInfo>, Error> {
+    use crate::schema::schema::users_queue::dsl::*;
+    users_queue
+        .filter(user_uuid.eq(user))
+        .filter(status.eq("SEARCHING"))
+        .order(created_at.desc())
+        .select((
+            id,
+            user_uuid,
+            status,
+            created_at,
+            updated_at,
+            pickup_latitude,
+            pickup_longitude,
+            order_weight_kg,
+            order_volume_liters,
+            zone_id,
+            priority,
+        ))
+        .first::<UserQueueInfo>(db_conn)
+        .await
+        .optional()
+}
+
 pub async fn get_last_user_try(db_conn: &mut DbConn<'_>, user: Uuid) -> Result<LastAttemp, Error> {
     use crate::schema::schema::users_queue::dsl::*;
     users_queue
@@ -89,3 +212,17 @@ pub async fn get_last_user_try(db_conn: &mut DbConn<'_>, user: Uuid) -> Result<L
         .get_result::<LastAttemp>(db_conn)
         .await
 }
+
+pub async fn select_queue_created_between(
+    db_conn: &mut DbConn<'_>,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<Vec<NaiveDateTime>, Error> {
+    use crate::schema::schema::users_queue::dsl::*;
+    users_queue
+        .filter(created_at.ge(from))
+        .filter(created_at.lt(to))
+        .select(created_at)
+        .load::<NaiveDateTime>(db_conn)
+        .await
+}
@@ -0,0 +1,120 @@
+use crate::models::ratings_model::*;
+use crate::resources::postgres::DbConn;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+pub async fn create_rating(
+    db_conn: &mut DbConn<'_>,
+    rating: CreateCourierRating,
+) -> Result<CourierRating, Error> {
+    use crate::schema::schema::courier_ratings::dsl::*;
+    diesel::insert_into(courier_ratings)
+        .values(rating)
+        .get_result(db_conn)
+        .await
+}
+
+pub async fn select_assignment_rating(
+    db_conn: &mut DbConn<'_>,
+    assignment: i64,
+) -> Result<Option<CourierRating>, Error> {
+    use crate::schema::schema::courier_ratings::dsl::*;
+    courier_ratings
+        .filter(assignment_id.eq(assignment))
+        .get_result::<CourierRating>(db_conn)
+        .await
+        .optional()
+}
+
+// Count and sum of all scores the courier has ever got
+pub async fn select_courier_scores_summary(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<(i64, Option<f64>), Error> {
+    use crate::schema::schema::courier_ratings::dsl::*;
+    courier_ratings
+        .filter(courier_uuid.eq(courier))
+        .select((diesel::dsl::count_star(), diesel::dsl::sum(score)))
+        .get_result::<(i64, Option<f64>)>(db_conn)
+        .await
+}
+
+pub async fn select_recent_reviews(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    reviews_limit: i64,
+) -> Result<Vec<CourierReview>, Error> {
+    use crate::schema::schema::courier_ratings::dsl::*;
+    courier_ratings
+        .filter(courier_uuid.eq(courier))
+        .order(created_at.desc())
+        .limit(reviews_limit)
+        .select((assignment_id, score, comment, created_at))
+        .load::<CourierReview>(db_conn)
+        .await
+}
+
+pub async fn select_courier_rating_summary(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<Option<CourierRatingSummary>, Error> {
+    use crate::schema::schema::couriers::dsl::*;
+    couriers
+        .filter(user_uuid.eq(courier))
+        .select((user_uuid, rating, ratings_count))
+        .get_result::<CourierRatingSummary>(db_conn)
+        .await
+        .optional()
+}
+
+// Couriers with equal rating are ordered by number of ratings and then by uuid,
+// so pages stay stable between requests
+pub async fn select_couriers_rating_summaries(
+    db_conn: &mut DbConn<'_>,
+    sort: RatingsSort,
+    offset: i64,
+    page_limit: i64,
+) -> Result<Vec<CourierRatingSummary>, Error> {
+    use crate::schema::schema::couriers::dsl::*;
+    let query = couriers
+        .filter(onboarding_status.eq("APPROVED"))
+        .select((user_uuid, rating, ratings_count))
+        .into_boxed();
+    let query = match sort {
+        RatingsSort::RatingDesc => query.order((rating.desc(), ratings_count.desc())),
+        RatingsSort::RatingAsc => query.order((rating.asc(), ratings_count.desc())),
+    };
+    query
+        .then_order_by(user_uuid.asc())
+        .offset(offset)
+        .limit(page_limit)
+        .load::<CourierRatingSummary>(db_conn)
+        .await
+}
+
+pub async fn count_approved_couriers(db_conn: &mut DbConn<'_>) -> Result<i64, Error> {
+    use crate::schema::schema::couriers::dsl::*;
+    couriers
+        .filter(onboarding_status.eq("APPROVED"))
+        .count()
+        .get_result::<i64>(db_conn)
+        .await
+}
+
+pub async fn select_average_score_between(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<Option<f64>, Error> {
+    use crate::schema::schema::courier_ratings::dsl::*;
+    courier_ratings
+        .filter(courier_uuid.eq(courier))
+        .filter(created_at.ge(from).and(created_at.lt(to)))
+        .select(diesel::dsl::avg(score))
+        .get_result::<Option<f64>>(db_conn)
+        .await
+}
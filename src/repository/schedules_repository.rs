@@ -0,0 +1,99 @@
+use crate::models::schedules_model::*;
+use crate::resources::postgres::DbConn;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+pub async fn select_courier_availability(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<Vec<AvailabilityWindow>, Error> {
+    use crate::schema::schema::courier_availability::dsl::*;
+    courier_availability
+        .filter(courier_uuid.eq(courier))
+        .order((day_of_week.asc(), start_time.asc()))
+        .load::<AvailabilityWindow>(db_conn)
+        .await
+}
+
+pub async fn select_all_availability(
+    db_conn: &mut DbConn<'_>,
+) -> Result<Vec<AvailabilityWindow>, Error> {
+    use crate::schema::schema::courier_availability::dsl::*;
+    courier_availability
+        .load::<AvailabilityWindow>(db_conn)
+        .await
+}
+
+pub async fn replace_courier_availability(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    windows: Vec<CreateAvailabilityWindow>,
+) -> Result<(), Error> {
+    use crate::schema::schema::courier_availability::dsl::*;
+    diesel::delete(courier_availability.filter(courier_uuid.eq(courier)))
+        .execute(db_conn)
+        .await?;
+    diesel::insert_into(courier_availability)
+        .values(windows)
+        .execute(db_conn)
+        .await?;
+    Ok(())
+}
+
+pub async fn create_planned_shift(
+    db_conn: &mut DbConn<'_>,
+    shift: CreatePlannedShift,
+) -> Result<PlannedShift, Error> {
+    use crate::schema::schema::planned_shifts::dsl::*;
+    diesel::insert_into(planned_shifts)
+        .values(shift)
+        .get_result(db_conn)
+        .await
+}
+
+pub async fn delete_planned_shift(db_conn: &mut DbConn<'_>, shift_id: i64) -> Result<usize, Error> {
+    use crate::schema::schema::planned_shifts::dsl::*;
+    diesel::delete(planned_shifts.find(shift_id))
+        .execute(db_conn)
+        .await
+}
+
+// Shifts overlapping with the period, of one courier if it's given
+pub async fn select_planned_shifts_between(
+    db_conn: &mut DbConn<'_>,
+    courier: Option<Uuid>,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<Vec<PlannedShift>, Error> {
+    use crate::schema::schema::planned_shifts::dsl::*;
+    let mut query = planned_shifts
+        .filter(starts_at.lt(to))
+        .filter(ends_at.gt(from))
+        .into_boxed();
+    if let Some(courier) = courier {
+        query = query.filter(courier_uuid.eq(courier));
+    }
+    query
+        .order((starts_at.asc(), id.asc()))
+        .load::<PlannedShift>(db_conn)
+        .await
+}
+
+pub async fn select_couriers_planned_at(
+    db_conn: &mut DbConn<'_>,
+    couriers: &[Uuid],
+    moment: NaiveDateTime,
+) -> Result<Vec<Uuid>, Error> {
+    use crate::schema::schema::planned_shifts::dsl::*;
+    planned_shifts
+        .filter(courier_uuid.eq_any(couriers))
+        .filter(starts_at.le(moment))
+        .filter(ends_at.gt(moment))
+        .select(courier_uuid)
+        .distinct()
+        .load::<Uuid>(db_conn)
+        .await
+}
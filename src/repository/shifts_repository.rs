@@ -0,0 +1,72 @@
+use crate::models::shifts_model::*;
+use crate::resources::postgres::DbConn;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+pub async fn create_shift(
+    db_conn: &mut DbConn<'_>,
+    shift: CreateShift,
+) -> Result<CourierShift, Error> {
+    use crate::schema::schema::courier_shifts::dsl::*;
+    diesel::insert_into(courier_shifts)
+        .values(shift)
+        .get_result(db_conn)
+        .await
+}
+
+pub async fn select_open_shift(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<Option<CourierShift>, Error> {
+    use crate::schema::schema::courier_shifts::dsl::*;
+    courier_shifts
+        .filter(courier_uuid.eq(courier).and(status.ne("FINISHED")))
+        .get_result::<CourierShift>(db_conn)
+        .await
+        .optional()
+}
+
+pub async fn update_shift(
+    db_conn: &mut DbConn<'_>,
+    shift_id: i64,
+    new_info: UpdateShift,
+) -> Result<CourierShift, Error> {
+    use crate::schema::schema::courier_shifts::dsl::*;
+    diesel::update(courier_shifts.find(shift_id))
+        .set(new_info)
+        .get_result(db_conn)
+        .await
+}
+
+pub async fn select_courier_shifts(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    shifts_limit: i64,
+) -> Result<Vec<CourierShift>, Error> {
+    use crate::schema::schema::courier_shifts::dsl::*;
+    courier_shifts
+        .filter(courier_uuid.eq(courier))
+        .order(started_at.desc())
+        .limit(shifts_limit)
+        .load::<CourierShift>(db_conn)
+        .await
+}
+
+// Shifts overlapping the period, including unfinished ones
+pub async fn select_shifts_between(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<Vec<CourierShift>, Error> {
+    use crate::schema::schema::courier_shifts::dsl::*;
+    courier_shifts
+        .filter(courier_uuid.eq(courier))
+        .filter(started_at.lt(to))
+        .filter(finished_at.is_null().or(finished_at.gt(from)))
+        .load::<CourierShift>(db_conn)
+        .await
+}
@@ -0,0 +1,43 @@
+use crate::models::vehicles_model::*;
+use crate::resources::postgres::DbConn;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+pub async fn upsert_vehicle(
+    db_conn: &mut DbConn<'_>,
+    vehicle: UpsertCourierVehicle,
+) -> Result<CourierVehicle, Error> {
+    use crate::schema::schema::courier_vehicles::dsl::*;
+    diesel::insert_into(courier_vehicles)
+        .values(&vehicle)
+        .on_conflict(courier_uuid)
+        .do_update()
+        .set(&vehicle)
+        .get_result(db_conn)
+        .await
+}
+
+pub async fn select_vehicle(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<Option<CourierVehicle>, Error> {
+    use crate::schema::schema::courier_vehicles::dsl::*;
+    courier_vehicles
+        .find(courier)
+        .get_result::<CourierVehicle>(db_conn)
+        .await
+        .optional()
+}
+
+pub async fn select_vehicles(
+    db_conn: &mut DbConn<'_>,
+    couriers: &[Uuid],
+) -> Result<Vec<CourierVehicle>, Error> {
+    use crate::schema::schema::courier_vehicles::dsl::*;
+    courier_vehicles
+        .filter(courier_uuid.eq_any(couriers))
+        .load::<CourierVehicle>(db_conn)
+        .await
+}
@@ -0,0 +1,148 @@
+use crate::models::zones_model::*;
+use crate::resources::postgres::DbConn;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+pub async fn create_zone(db_conn: &mut DbConn<'_>, zone: UpsertZone) -> Result<Zone, Error> {
+    use crate::schema::schema::zones::dsl::*;
+    diesel::insert_into(zones)
+        .values(zone)
+        .get_result(db_conn)
+        .await
+}
+
+pub async fn update_zone(
+    db_conn: &mut DbConn<'_>,
+    zone_id: i64,
+    zone: UpsertZone,
+) -> Result<Option<Zone>, Error> {
+    use crate::schema::schema::zones::dsl::*;
+    diesel::update(zones.find(zone_id))
+        .set(zone)
+        .get_result(db_conn)
+        .await
+        .optional()
+}
+
+pub async fn delete_zone(db_conn: &mut DbConn<'_>, zone_id: i64) -> Result<usize, Error> {
+    use crate::schema::schema::zones::dsl::*;
+    diesel::delete(zones.find(zone_id)).execute(db_conn).await
+}
+
+pub async fn select_all_zones(db_conn: &mut DbConn<'_>) -> Result<Vec<Zone>, Error> {
+    use crate::schema::schema::zones::dsl::*;
+    zones.order(id.asc()).load::<Zone>(db_conn).await
+}
+
+pub async fn count_zones(db_conn: &mut DbConn<'_>, zones_ids: &[i64]) -> Result<i64, Error> {
+    use crate::schema::schema::zones::dsl::*;
+    zones
+        .filter(id.eq_any(zones_ids))
+        .count()
+        .get_result(db_conn)
+        .await
+}
+
+pub async fn select_zone_neighbours(
+    db_conn: &mut DbConn<'_>,
+    zone: i64,
+) -> Result<Vec<i64>, Error> {
+    use crate::schema::schema::zone_neighbours::dsl::*;
+    zone_neighbours
+        .filter(zone_id.eq(zone))
+        .select(neighbour_id)
+        .load::<i64>(db_conn)
+        .await
+}
+
+pub async fn select_all_zone_neighbours(
+    db_conn: &mut DbConn<'_>,
+) -> Result<Vec<(i64, i64)>, Error> {
+    use crate::schema::schema::zone_neighbours::dsl::*;
+    zone_neighbours
+        .select((zone_id, neighbour_id))
+        .load::<(i64, i64)>(db_conn)
+        .await
+}
+
+// Both directions of every neighbourhood are replaced
+pub async fn replace_zone_neighbours(
+    db_conn: &mut DbConn<'_>,
+    zone: i64,
+    neighbours: &[i64],
+) -> Result<(), Error> {
+    use crate::schema::schema::zone_neighbours::dsl::*;
+    diesel::delete(zone_neighbours.filter(zone_id.eq(zone).or(neighbour_id.eq(zone))))
+        .execute(db_conn)
+        .await?;
+    let new_neighbours: Vec<CreateZoneNeighbour> = neighbours
+        .iter()
+        .flat_map(|neighbour| {
+            [
+                CreateZoneNeighbour {
+                    zone_id: zone,
+                    neighbour_id: *neighbour,
+                },
+                CreateZoneNeighbour {
+                    zone_id: *neighbour,
+                    neighbour_id: zone,
+                },
+            ]
+        })
+        .collect();
+    diesel::insert_into(zone_neighbours)
+        .values(new_neighbours)
+        .execute(db_conn)
+        .await?;
+    Ok(())
+}
+
+pub async fn select_courier_zones(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<Vec<i64>, Error> {
+    use crate::schema::schema::courier_zones::dsl::*;
+    courier_zones
+        .filter(courier_uuid.eq(courier))
+        .order(zone_id.asc())
+        .select(zone_id)
+        .load::<i64>(db_conn)
+        .await
+}
+
+pub async fn select_couriers_zones(
+    db_conn: &mut DbConn<'_>,
+    couriers: &[Uuid],
+) -> Result<Vec<(Uuid, i64)>, Error> {
+    use crate::schema::schema::courier_zones::dsl::*;
+    courier_zones
+        .filter(courier_uuid.eq_any(couriers))
+        .select((courier_uuid, zone_id))
+        .load::<(Uuid, i64)>(db_conn)
+        .await
+}
+
+pub async fn replace_courier_zones(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    zones: &[i64],
+) -> Result<(), Error> {
+    use crate::schema::schema::courier_zones::dsl::*;
+    diesel::delete(courier_zones.filter(courier_uuid.eq(courier)))
+        .execute(db_conn)
+        .await?;
+    let new_zones: Vec<CreateCourierZone> = zones
+        .iter()
+        .map(|zone| CreateCourierZone {
+            courier_uuid: courier,
+            zone_id: *zone,
+        })
+        .collect();
+    diesel::insert_into(courier_zones)
+        .values(new_zones)
+        .execute(db_conn)
+        .await?;
+    Ok(())
+}
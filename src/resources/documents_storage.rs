@@ -0,0 +1,58 @@
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+// Place where couriers' documents are kept.
+// Keys are relative paths like `<courier_uuid>/<document_type>-<uuid>`
+#[tonic::async_trait]
+pub trait DocumentsStorage: Send + Sync {
+    async fn save(&self, key: &str, content: &[u8]) -> io::Result<()>;
+    async fn load(&self, key: &str) -> io::Result<Vec<u8>>;
+    async fn delete(&self, key: &str) -> io::Result<()>;
+}
+
+pub struct LocalDocumentsStorage {
+    root: PathBuf,
+}
+
+impl LocalDocumentsStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalDocumentsStorage { root: root.into() }
+    }
+
+    // Keys are generated by the service, but still they must not leave the storage directory
+    fn path(&self, key: &str) -> io::Result<PathBuf> {
+        let key = Path::new(key);
+        let is_safe = key
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+        if !is_safe {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Invalid document key",
+            ));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+#[tonic::async_trait]
+impl DocumentsStorage for LocalDocumentsStorage {
+    async fn save(&self, key: &str, content: &[u8]) -> io::Result<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, content).await
+    }
+
+    async fn load(&self, key: &str) -> io::Result<Vec<u8>> {
+        tokio::fs::read(self.path(key)?).await
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::utils::backoff::retry_delay;
+use crate::utils::circuit_breaker::CircuitBreaker;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Code, Status};
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy)]
+pub struct GrpcSettings {
+    pub connect_timeout: Duration,
+    // Deadline of every call
+    pub request_timeout: Duration,
+    // Attempts of idempotent calls, other calls are made once
+    pub retry_attempts: u32,
+    pub retry_min_backoff: Duration,
+    pub retry_max_backoff: Duration,
+    pub breaker_failure_threshold: u32,
+    pub breaker_reset_timeout: Duration,
+}
+
+// Channel to another service shared by all calls to it. It's connected on the first
+// call and reconnects by itself, clones share the connection and the circuit breaker
+#[derive(Clone)]
+pub struct GrpcChannel {
+    name: String,
+    endpoint: Endpoint,
+    channel: Channel,
+    breaker: Arc<CircuitBreaker>,
+    settings: GrpcSettings,
+}
+
+impl GrpcChannel {
+    pub fn new(name: &str, endpoint: Endpoint, settings: GrpcSettings) -> Self {
+        let endpoint = endpoint
+            .connect_timeout(settings.connect_timeout)
+            .timeout(settings.request_timeout);
+        let channel = endpoint.connect_lazy();
+        let breaker = CircuitBreaker::new(
+            name,
+            settings.breaker_failure_threshold,
+            settings.breaker_reset_timeout,
+        );
+        GrpcChannel {
+            name: name.to_owned(),
+            endpoint,
+            channel,
+            breaker: Arc::new(breaker),
+            settings,
+        }
+    }
+
+    // Separate connection is made, so the check doesn't affect the shared one
+    pub async fn is_reachable(&self) -> bool {
+        self.endpoint.connect().await.is_ok()
+    }
+
+    // Idempotent calls are retried with back-off when the service is unreachable
+    // or too slow. While the circuit is open calls fail right away
+    pub async fn call<T, F, Fut>(&self, idempotent: bool, mut request: F) -> Result<T, Status>
+    where
+        F: FnMut(Channel) -> Fut,
+        Fut: Future<Output = Result<T, Status>>,
+    {
+        let attempts = if idempotent {
+            self.settings.retry_attempts.max(1)
+        } else {
+            1
+        };
+        let mut attempt = 1;
+        loop {
+            if !self.breaker.allow_request() {
+                return Err(Status::unavailable(format!(
+                    "{} service is unavailable, circuit is open",
+                    self.name
+                )));
+            }
+            match request(self.channel.clone()).await {
+                Err(status) if is_transient(&status) => {
+                    self.breaker.record_failure();
+                    if attempt >= attempts {
+                        return Err(status);
+                    }
+                    warn!(
+                        "Call to {} service failed, attempt {attempt} of {attempts}, {status}",
+                        self.name
+                    );
+                    let delay = retry_delay(
+                        self.settings.retry_min_backoff,
+                        self.settings.retry_max_backoff,
+                        attempt,
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                // Errors returned by the service itself mean it's healthy
+                result => {
+                    self.breaker.record_success();
+                    return result;
+                }
+            }
+        }
+    }
+}
+
+// Connection errors, expired deadlines and exhausted quotas, the call can succeed
+// if it's repeated. Other errors come from the service itself and won't change
+fn is_transient(status: &Status) -> bool {
+    matches!(
+        status.code(),
+        Code::Unavailable | Code::DeadlineExceeded | Code::ResourceExhausted
+    )
+}
@@ -0,0 +1,119 @@
+use crate::resources::grpc_channels::{GrpcChannel, GrpcSettings};
+use crate::utils::grpc::analytics_grpc::analytics_client::AnalyticsClient;
+use crate::utils::grpc::orders_grpc::orders_client::OrdersClient;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use tonic::transport::{Channel, Endpoint};
+use tonic::Status;
+
+// Address of a downstream service, parsed from configuration.
+// Each service has its own type, so it can't be given to another service's client
+pub trait ServiceAddress {
+    fn endpoint(&self) -> &Endpoint;
+}
+
+#[derive(Debug, Clone)]
+pub struct OrdersAddress(Endpoint);
+
+#[derive(Debug, Clone)]
+pub struct AnalyticsAddress(Endpoint);
+
+impl FromStr for OrdersAddress {
+    type Err = tonic::transport::Error;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        Endpoint::from_shared(address.to_owned()).map(OrdersAddress)
+    }
+}
+
+impl FromStr for AnalyticsAddress {
+    type Err = tonic::transport::Error;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        Endpoint::from_shared(address.to_owned()).map(AnalyticsAddress)
+    }
+}
+
+impl ServiceAddress for OrdersAddress {
+    fn endpoint(&self) -> &Endpoint {
+        &self.0
+    }
+}
+
+impl ServiceAddress for AnalyticsAddress {
+    fn endpoint(&self) -> &Endpoint {
+        &self.0
+    }
+}
+
+// Generated client of a downstream service
+pub trait GrpcClient: Sized {
+    const SERVICE: &'static str;
+
+    type Address: ServiceAddress;
+
+    fn from_channel(channel: Channel) -> Self;
+}
+
+impl GrpcClient for OrdersClient<Channel> {
+    const SERVICE: &'static str = "Orders";
+
+    type Address = OrdersAddress;
+
+    fn from_channel(channel: Channel) -> Self {
+        OrdersClient::new(channel)
+    }
+}
+
+impl GrpcClient for AnalyticsClient<Channel> {
+    const SERVICE: &'static str = "Analytics";
+
+    type Address = AnalyticsAddress;
+
+    fn from_channel(channel: Channel) -> Self {
+        AnalyticsClient::new(channel)
+    }
+}
+
+// Channel bound to the client type of the service it's connected to,
+// it's created only from that service's address
+pub struct ServiceClient<C> {
+    channel: GrpcChannel,
+    client: PhantomData<fn() -> C>,
+}
+
+pub type OrdersService = ServiceClient<OrdersClient<Channel>>;
+pub type AnalyticsService = ServiceClient<AnalyticsClient<Channel>>;
+
+impl<C: GrpcClient> ServiceClient<C> {
+    pub fn new(address: &C::Address, settings: GrpcSettings) -> Self {
+        ServiceClient {
+            channel: GrpcChannel::new(C::SERVICE, address.endpoint().clone(), settings),
+            client: PhantomData,
+        }
+    }
+
+    pub async fn is_reachable(&self) -> bool {
+        self.channel.is_reachable().await
+    }
+
+    pub async fn call<T, F, Fut>(&self, idempotent: bool, mut request: F) -> Result<T, Status>
+    where
+        F: FnMut(C) -> Fut,
+        Fut: Future<Output = Result<T, Status>>,
+    {
+        self.channel
+            .call(idempotent, |channel| request(C::from_channel(channel)))
+            .await
+    }
+}
+
+impl<C> Clone for ServiceClient<C> {
+    fn clone(&self) -> Self {
+        ServiceClient {
+            channel: self.channel.clone(),
+            client: PhantomData,
+        }
+    }
+}
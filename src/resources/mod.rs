@@ -1 +1,5 @@
+pub mod documents_storage;
+pub mod grpc_channels;
+pub mod grpc_clients;
+pub mod notifications;
 pub mod postgres;
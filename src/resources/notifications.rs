@@ -0,0 +1,75 @@
+use futures::{stream, StreamExt};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tokio_postgres::{AsyncMessage, NoTls};
+use tracing::{error, info};
+
+// Notified by triggers on queue, couriers and offers changes
+pub const DISTRIBUTION_CHANNEL: &str = "courier_distribution";
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+// Postgres notifications for courier distributor.
+// Diesel connections can't LISTEN, so a separate tokio-postgres connection is kept
+pub struct DistributionEvents {
+    notify: Arc<Notify>,
+    listener: JoinHandle<()>,
+}
+
+impl DistributionEvents {
+    pub fn listen(database_url: String) -> Self {
+        let notify = Arc::new(Notify::new());
+        let listener = tokio::spawn(listen_untill_stopped(database_url, notify.clone()));
+        DistributionEvents { notify, listener }
+    }
+
+    // Returns on the next event or after `timeout`. Event received while
+    // nobody was waiting makes the next call return right away
+    pub async fn wait(&self, timeout: Duration) {
+        let _ = tokio::time::timeout(timeout, self.notify.notified()).await;
+    }
+}
+
+impl Drop for DistributionEvents {
+    fn drop(&mut self) {
+        self.listener.abort();
+    }
+}
+
+// Distributor keeps polling with its fallback interval while listener reconnects
+async fn listen_untill_stopped(database_url: String, notify: Arc<Notify>) {
+    loop {
+        match listen(&database_url, &notify).await {
+            Ok(()) => info!("Distribution events connection closed, reconnecting"),
+            Err(e) => error!("Error listening to distribution events {e}"),
+        }
+        tokio::time::sleep(RECONNECT_INTERVAL).await;
+    }
+}
+
+async fn listen(database_url: &str, notify: &Notify) -> Result<(), tokio_postgres::Error> {
+    let (client, mut connection) = tokio_postgres::connect(database_url, NoTls).await?;
+    // Connection has to be polled for LISTEN itself to be executed
+    let mut messages = stream::poll_fn(move |cx| connection.poll_message(cx));
+    let listen_query = format!("LISTEN {DISTRIBUTION_CHANNEL}");
+    let subscription = client.batch_execute(&listen_query);
+    tokio::pin!(subscription);
+    let mut subscribed = false;
+    loop {
+        tokio::select! {
+            result = &mut subscription, if !subscribed => {
+                result?;
+                subscribed = true;
+                // Anything could have happened while there was no connection
+                notify.notify_one();
+            }
+            message = messages.next() => match message {
+                Some(Ok(AsyncMessage::Notification(_))) => notify.notify_one(),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e),
+                None => return Ok(()),
+            },
+        }
+    }
+}
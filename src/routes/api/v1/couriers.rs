@@ -6,7 +6,10 @@ use crate::utils::permission_policy::Policy;
 use actix_web::web;
 use tracing_actix_web::TracingLogger;
 
+const MAX_DOCUMENT_SIZE: usize = 10 * 1024 * 1024;
+
 pub fn api_v1_couriers_config(cfg: &mut web::ServiceConfig, jwt_secret: String, policy: Policy) {
+    let users_policy_mw = PermissionsMiddlewareFactory::new(policy.user_policy.clone());
     let courier_policy_mw = PermissionsMiddlewareFactory::new(policy.courier_policy.clone());
     let admin_policy_mw = PermissionsMiddlewareFactory::new(policy.admin_policy.clone());
     let jwt_middleware = JwtMiddleware { jwt_secret };
@@ -20,15 +23,192 @@ pub fn api_v1_couriers_config(cfg: &mut web::ServiceConfig, jwt_secret: String,
                     .route(web::get().to(get_all_couriers))
                     .wrap(admin_policy_mw.clone()),
             )
+            // Have to be registered before "/{uuid}" to not be captured by it
             .service(
-                web::resource("/{uuid}")
-                    .route(web::patch().to(get_courier_info))
-                    .wrap(admin_policy_mw),
+                web::resource("/onboarding")
+                    .route(web::get().to(get_couriers_onboarding))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/planned-shifts")
+                    .route(web::get().to(get_planned_shifts))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/planned-shifts/{id}")
+                    .route(web::delete().to(delete_planned_shift))
+                    .wrap(admin_policy_mw.clone()),
             )
+            .service(
+                web::resource("/capacity-forecast")
+                    .route(web::get().to(get_capacity_forecast))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/ratings")
+                    .route(web::get().to(get_couriers_ratings))
+                    .wrap(users_policy_mw.clone()),
+            )
+            // Courier's own resources have to be registered before "/{uuid}/..." ones
             .service(
                 web::resource("/me/")
                     .route(web::get().to(get_courier_profile))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/stats")
+                    .route(web::get().to(get_courier_stats))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/zones")
+                    .route(web::get().to(get_own_zones))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/availability")
+                    .route(web::get().to(get_availability))
+                    .route(web::put().to(set_availability))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/planned-shifts")
+                    .route(web::get().to(get_own_planned_shifts))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/heartbeat")
+                    .route(web::post().to(heartbeat))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/shifts")
+                    .route(web::get().to(get_shifts_history))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/shift/start")
+                    .route(web::post().to(start_shift))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/shift/pause")
+                    .route(web::post().to(pause_shift))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/shift/end")
+                    .route(web::post().to(end_shift))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/assignments")
+                    .route(web::get().to(get_active_assignments))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/assignments/{id}/pick-up")
+                    .route(web::post().to(pick_up_assignment))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/assignments/{id}/deliver")
+                    .route(web::post().to(deliver_assignment))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/assignments/{id}/cancel")
+                    .route(web::post().to(cancel_assignment))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/offers")
+                    .route(web::get().to(get_courier_offers))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/offers/{id}/accept")
+                    .route(web::post().to(accept_offer))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/offers/{id}/decline")
+                    .route(web::post().to(decline_offer))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/locations")
+                    .route(web::post().to(report_locations))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/vehicle")
+                    .route(web::get().to(get_vehicle_profile))
+                    .route(web::put().to(set_vehicle_profile))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/onboarding")
+                    .route(web::get().to(get_onboarding_info))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/onboarding/submit")
+                    .route(web::post().to(submit_onboarding))
+                    .wrap(courier_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/documents/{document_type}")
+                    .app_data(web::PayloadConfig::new(MAX_DOCUMENT_SIZE))
+                    .route(web::put().to(upload_document))
                     .wrap(courier_policy_mw),
+            )
+            .service(
+                web::resource("/{uuid}/rating")
+                    .route(web::get().to(get_courier_rating))
+                    .wrap(users_policy_mw),
+            )
+            .service(
+                web::resource("/{uuid}/onboarding")
+                    .route(web::get().to(get_courier_onboarding))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/{uuid}/onboarding/approve")
+                    .route(web::post().to(approve_courier))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/{uuid}/onboarding/reject")
+                    .route(web::post().to(reject_courier))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/{uuid}/zones")
+                    .route(web::get().to(get_courier_zones))
+                    .route(web::put().to(set_courier_zones))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/{uuid}/stats")
+                    .route(web::get().to(get_courier_stats_by_uuid))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/{uuid}/planned-shifts")
+                    .route(web::get().to(get_courier_planned_shifts))
+                    .route(web::post().to(create_planned_shift))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/{uuid}/documents/{id}")
+                    .route(web::get().to(get_courier_document))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/{uuid}")
+                    .route(web::patch().to(get_courier_info))
+                    .wrap(admin_policy_mw),
             ),
     );
 }
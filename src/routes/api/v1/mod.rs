@@ -1,3 +1,5 @@
 pub mod couriers;
+pub mod system;
 pub mod users;
 pub mod v1_config;
+pub mod zones;
@@ -0,0 +1,38 @@
+use crate::handlers::system_handler::*;
+use crate::middleware::jwt_middleware::JwtMiddleware;
+use crate::middleware::logs_middleware::CustomRootSpanBuilder;
+use crate::middleware::permissions_middleware::PermissionsMiddlewareFactory;
+use crate::utils::permission_policy::Policy;
+use actix_web::web;
+use tracing_actix_web::TracingLogger;
+
+pub fn api_v1_system_config(cfg: &mut web::ServiceConfig, jwt_secret: String, policy: Policy) {
+    let admin_policy_mw = PermissionsMiddlewareFactory::new(policy.admin_policy.clone());
+    let jwt_middleware = JwtMiddleware { jwt_secret };
+
+    cfg.service(
+        web::scope("api/v1/system")
+            .wrap(TracingLogger::<CustomRootSpanBuilder>::new())
+            .wrap(jwt_middleware)
+            .service(
+                web::resource("/distributor")
+                    .route(web::get().to(get_distributor_status))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/outbox")
+                    .route(web::get().to(get_outbox_messages))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/outbox/{id}")
+                    .route(web::get().to(get_outbox_message))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/outbox/{id}/replay")
+                    .route(web::post().to(replay_outbox_message))
+                    .wrap(admin_policy_mw),
+            ),
+    );
+}
@@ -38,6 +38,16 @@ pub fn api_v1_users_config(cfg: &mut web::ServiceConfig, jwt_secret: String, pol
             .service(
                 web::resource("/me/")
                     .route(web::get().to(get_user_profile))
+                    .wrap(users_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/search/cancel")
+                    .route(web::post().to(cancel_courier_search))
+                    .wrap(users_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/me/search/events")
+                    .route(web::get().to(watch_courier_search))
                     .wrap(users_policy_mw),
             ),
     );
@@ -1,5 +1,5 @@
 use crate::{
-    routes::api::v1::{couriers, users},
+    routes::api::v1::{couriers, system, users, zones},
     utils::permission_policy::Policy,
 };
 use actix_web::web;
@@ -8,5 +8,13 @@ pub fn api_v1_config(cfg: &mut web::ServiceConfig, jwt_secret: String, policy: P
     let cloned_jwt_secret = jwt_secret.clone();
     let cloned_policy = policy.clone();
     cfg.configure(move |cfg| users::api_v1_users_config(cfg, cloned_jwt_secret, cloned_policy));
-    cfg.configure(move |cfg| couriers::api_v1_couriers_config(cfg, jwt_secret, policy));
+    let cloned_jwt_secret = jwt_secret.clone();
+    let cloned_policy = policy.clone();
+    cfg.configure(move |cfg| {
+        couriers::api_v1_couriers_config(cfg, cloned_jwt_secret, cloned_policy)
+    });
+    let cloned_jwt_secret = jwt_secret.clone();
+    let cloned_policy = policy.clone();
+    cfg.configure(move |cfg| zones::api_v1_zones_config(cfg, cloned_jwt_secret, cloned_policy));
+    cfg.configure(move |cfg| system::api_v1_system_config(cfg, jwt_secret, policy));
 }
@@ -0,0 +1,35 @@
+use crate::handlers::zones_handler::*;
+use crate::middleware::jwt_middleware::JwtMiddleware;
+use crate::middleware::logs_middleware::CustomRootSpanBuilder;
+use crate::middleware::permissions_middleware::PermissionsMiddlewareFactory;
+use crate::utils::permission_policy::Policy;
+use actix_web::web;
+use tracing_actix_web::TracingLogger;
+
+pub fn api_v1_zones_config(cfg: &mut web::ServiceConfig, jwt_secret: String, policy: Policy) {
+    let admin_policy_mw = PermissionsMiddlewareFactory::new(policy.admin_policy.clone());
+    let jwt_middleware = JwtMiddleware { jwt_secret };
+
+    cfg.service(
+        web::scope("api/v1/zones")
+            .wrap(TracingLogger::<CustomRootSpanBuilder>::new())
+            .wrap(jwt_middleware)
+            .service(
+                web::resource("/")
+                    .route(web::get().to(get_zones))
+                    .route(web::post().to(create_zone))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/{id}")
+                    .route(web::put().to(update_zone))
+                    .route(web::delete().to(delete_zone))
+                    .wrap(admin_policy_mw.clone()),
+            )
+            .service(
+                web::resource("/{id}/neighbours")
+                    .route(web::put().to(set_zone_neighbours))
+                    .wrap(admin_policy_mw),
+            ),
+    );
+}
@@ -1,5 +1,116 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    assignments (id) {
+        id -> Int8,
+        user_uuid -> Uuid,
+        courier_uuid -> Uuid,
+        queue_id -> Nullable<Int8>,
+        status -> Text,
+        assigned_at -> Timestamp,
+        picked_up_at -> Nullable<Timestamp>,
+        delivered_at -> Nullable<Timestamp>,
+        cancelled_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    courier_availability (id) {
+        id -> Int8,
+        courier_uuid -> Uuid,
+        day_of_week -> Int2,
+        start_time -> Time,
+        end_time -> Time,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    courier_documents (id) {
+        id -> Int8,
+        courier_uuid -> Uuid,
+        document_type -> Text,
+        content_type -> Text,
+        size_bytes -> Int8,
+        storage_key -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    courier_locations (courier_uuid) {
+        courier_uuid -> Uuid,
+        latitude -> Float8,
+        longitude -> Float8,
+        recorded_at -> Timestamp,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    courier_offers (id) {
+        id -> Int8,
+        queue_id -> Int8,
+        user_uuid -> Uuid,
+        courier_uuid -> Uuid,
+        status -> Text,
+        expires_at -> Timestamp,
+        responded_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    courier_ratings (id) {
+        id -> Int8,
+        assignment_id -> Int8,
+        courier_uuid -> Uuid,
+        rater_uuid -> Uuid,
+        score -> Float8,
+        comment -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    courier_shifts (id) {
+        id -> Int8,
+        courier_uuid -> Uuid,
+        status -> Text,
+        started_at -> Timestamp,
+        paused_at -> Nullable<Timestamp>,
+        paused_seconds -> Int8,
+        finished_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    courier_vehicles (courier_uuid) {
+        courier_uuid -> Uuid,
+        vehicle_type -> Text,
+        max_weight_kg -> Float8,
+        max_volume_liters -> Float8,
+        max_concurrent_deliveries -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    courier_zones (courier_uuid, zone_id) {
+        courier_uuid -> Uuid,
+        zone_id -> Int8,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     couriers (user_uuid) {
         user_uuid -> Uuid,
@@ -7,6 +118,48 @@ diesel::table! {
         rating -> Float8,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        is_on_shift -> Bool,
+        ratings_count -> Int8,
+        onboarding_status -> Text,
+        rejection_reason -> Nullable<Text>,
+        last_seen_at -> Nullable<Timestamp>,
+        free_since -> Timestamp,
+    }
+}
+
+diesel::table! {
+    outbox_messages (id) {
+        id -> Int8,
+        kind -> Text,
+        payload -> Jsonb,
+        status -> Text,
+        attempts -> Int4,
+        last_error -> Nullable<Text>,
+        next_attempt_at -> Timestamp,
+        delivered_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    planned_shifts (id) {
+        id -> Int8,
+        courier_uuid -> Uuid,
+        starts_at -> Timestamp,
+        ends_at -> Timestamp,
+        created_by -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    service_leaders (name) {
+        name -> Text,
+        instance_id -> Text,
+        elected_at -> Timestamp,
+        heartbeat_at -> Timestamp,
     }
 }
 
@@ -33,13 +186,68 @@ diesel::table! {
         status -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        pickup_latitude -> Nullable<Float8>,
+        pickup_longitude -> Nullable<Float8>,
+        order_weight_kg -> Nullable<Float8>,
+        order_volume_liters -> Nullable<Float8>,
+        zone_id -> Nullable<Int8>,
+        priority -> Int4,
+    }
+}
+
+diesel::table! {
+    zone_neighbours (zone_id, neighbour_id) {
+        zone_id -> Int8,
+        neighbour_id -> Int8,
+    }
+}
+
+diesel::table! {
+    zones (id) {
+        id -> Int8,
+        name -> Text,
+        polygon -> Jsonb,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
+diesel::joinable!(assignments -> couriers (courier_uuid));
+diesel::joinable!(assignments -> users (user_uuid));
+diesel::joinable!(assignments -> users_queue (queue_id));
+diesel::joinable!(courier_availability -> couriers (courier_uuid));
+diesel::joinable!(courier_documents -> couriers (courier_uuid));
+diesel::joinable!(courier_locations -> couriers (courier_uuid));
+diesel::joinable!(courier_offers -> couriers (courier_uuid));
+diesel::joinable!(courier_offers -> users_queue (queue_id));
+diesel::joinable!(courier_ratings -> assignments (assignment_id));
+diesel::joinable!(courier_ratings -> couriers (courier_uuid));
+diesel::joinable!(courier_ratings -> users (rater_uuid));
+diesel::joinable!(courier_shifts -> couriers (courier_uuid));
+diesel::joinable!(courier_vehicles -> couriers (courier_uuid));
+diesel::joinable!(courier_zones -> couriers (courier_uuid));
+diesel::joinable!(courier_zones -> zones (zone_id));
 diesel::joinable!(couriers -> users (user_uuid));
+diesel::joinable!(planned_shifts -> couriers (courier_uuid));
+diesel::joinable!(planned_shifts -> users (created_by));
+diesel::joinable!(users_queue -> zones (zone_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    assignments,
+    courier_availability,
+    courier_documents,
+    courier_locations,
+    courier_offers,
+    courier_ratings,
+    courier_shifts,
+    courier_vehicles,
+    courier_zones,
     couriers,
+    outbox_messages,
+    planned_shifts,
+    service_leaders,
     users,
     users_queue,
+    zone_neighbours,
+    zones,
 );
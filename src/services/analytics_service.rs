@@ -0,0 +1,46 @@
+use crate::models::analytics_model::AnalyticsEvent;
+use crate::models::outbox_model::Notification;
+use crate::resources::postgres::DbConn;
+use crate::services::outbox_service::enqueue_notification;
+use crate::utils::configs::Config;
+use crate::utils::errors::AppError;
+use crate::utils::grpc::analytics_grpc::{
+    DeliveryEvent, SaveDeliveryEventsRequest, SaveDeliveryEventsResponse,
+};
+use tonic::{Response, Status};
+
+// Event is written to outbox in the transaction of the change it's about,
+// outbox dispatcher sends due events to Analytics service in batches
+pub async fn record_event(db_conn: &mut DbConn<'_>, event: AnalyticsEvent) -> Result<(), AppError> {
+    enqueue_notification(db_conn, &Notification::AnalyticsEvent(event)).await
+}
+
+// Analytics service deduplicates events by id, so the call can be retried
+pub async fn send_delivery_events(
+    config: &Config,
+    events: Vec<(i64, AnalyticsEvent)>,
+) -> Result<Response<SaveDeliveryEventsResponse>, Status> {
+    let request = SaveDeliveryEventsRequest {
+        events: events
+            .into_iter()
+            .map(|(event_id, event)| DeliveryEvent {
+                event_id,
+                event_type: event.event_type,
+                user_uuid: event.user_uuid.to_string(),
+                courier_uuid: event.courier_uuid.map(|courier| courier.to_string()),
+                queue_id: event.queue_id,
+                assignment_id: event.assignment_id,
+                occurred_at: event.occurred_at.to_string(),
+                duration_seconds: event.duration_seconds,
+                rating: event.rating,
+            })
+            .collect(),
+    };
+    config
+        .analytics_grpc
+        .call(true, |mut client| {
+            let request = request.clone();
+            async move { client.save_delivery_events(request).await }
+        })
+        .await
+}
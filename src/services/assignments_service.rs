@@ -0,0 +1,209 @@
+use crate::models::analytics_model::AnalyticsEvent;
+use crate::models::assignments_model::{Assignment, CreateAssignment, UpdateAssignment};
+use crate::models::couriers_model::UpdateCourier;
+use crate::models::vehicles_model::DEFAULT_MAX_CONCURRENT_DELIVERIES;
+use crate::repository::{
+    assignments_repository, couriers_repository, offers_repository, queue_repository,
+    vehicles_repository,
+};
+use crate::resources::postgres::DbConn;
+use crate::services::analytics_service::record_event;
+use crate::utils::errors::AppError;
+use chrono::Utc;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use uuid::Uuid;
+
+// Recording new assignment, courier stays free if he still has spare capacity.
+// Position in queue (if there is one) is completed in the same transaction
+pub async fn assign_courier(
+    db_conn: &mut DbConn<'_>,
+    user: Uuid,
+    courier: Uuid,
+    queue_id: Option<i64>,
+) -> Result<Assignment, AppError> {
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                if let Some(queue_id) = queue_id {
+                    let completed =
+                        queue_repository::finish_searching_position(db_conn, queue_id, "COMPLETED")
+                            .await?;
+                    if completed == 0 {
+                        return Err(AppError::conflict_error(
+                            "User isn't waiting for a courier anymore",
+                        ));
+                    }
+                }
+                let new_assignment = CreateAssignment {
+                    user_uuid: user,
+                    courier_uuid: courier,
+                    queue_id,
+                };
+                let assignment =
+                    assignments_repository::create_assignment(db_conn, new_assignment).await?;
+                refresh_courier_availability(db_conn, courier).await?;
+
+                // Waiting time is known only for assignments made from queue
+                let position = match queue_id {
+                    Some(queue_id) => {
+                        queue_repository::select_queue_position(db_conn, queue_id).await?
+                    }
+                    None => None,
+                };
+                let event = AnalyticsEvent {
+                    courier_uuid: Some(courier),
+                    queue_id,
+                    assignment_id: Some(assignment.id),
+                    duration_seconds: position.map(|position| {
+                        (assignment.assigned_at - position.created_at).num_seconds()
+                    }),
+                    ..AnalyticsEvent::new("COURIER_FOUND", user, assignment.assigned_at)
+                };
+                record_event(db_conn, event).await?;
+                Ok(assignment)
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+pub async fn get_assignment(
+    db_conn: &mut DbConn<'_>,
+    assignment_id: i64,
+) -> Result<Assignment, AppError> {
+    assignments_repository::select_assignment(db_conn, assignment_id)
+        .await
+        .map_err(AppError::db_error)?
+        .ok_or_else(|| AppError::not_found_error("Assignment not found"))
+}
+
+pub async fn get_active_assignments(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<Vec<Assignment>, AppError> {
+    assignments_repository::select_active_courier_assignments(db_conn, courier)
+        .await
+        .map_err(AppError::db_error)
+}
+
+// `courier` restricts transition to assignments of that courier only.
+// Orders service passes `None` because it acts on behalf of any courier
+pub async fn pick_up_assignment(
+    db_conn: &mut DbConn<'_>,
+    assignment_id: i64,
+    courier: Option<Uuid>,
+) -> Result<Assignment, AppError> {
+    change_assignment_status(db_conn, assignment_id, courier, "PICKED_UP").await
+}
+
+pub async fn deliver_assignment(
+    db_conn: &mut DbConn<'_>,
+    assignment_id: i64,
+    courier: Option<Uuid>,
+) -> Result<Assignment, AppError> {
+    change_assignment_status(db_conn, assignment_id, courier, "DELIVERED").await
+}
+
+pub async fn cancel_assignment(
+    db_conn: &mut DbConn<'_>,
+    assignment_id: i64,
+    courier: Option<Uuid>,
+) -> Result<Assignment, AppError> {
+    change_assignment_status(db_conn, assignment_id, courier, "CANCELLED").await
+}
+
+// Allowed lifecycle: ASSIGNED -> PICKED_UP -> DELIVERED
+// Cancellation is possible untill the order is delivered
+fn is_transition_allowed(current_status: &str, new_status: &str) -> bool {
+    matches!(
+        (current_status, new_status),
+        ("ASSIGNED", "PICKED_UP")
+            | ("PICKED_UP", "DELIVERED")
+            | ("ASSIGNED", "CANCELLED")
+            | ("PICKED_UP", "CANCELLED")
+    )
+}
+
+async fn change_assignment_status(
+    db_conn: &mut DbConn<'_>,
+    assignment_id: i64,
+    courier: Option<Uuid>,
+    new_status: &'static str,
+) -> Result<Assignment, AppError> {
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let assignment =
+                    assignments_repository::select_assignment_for_update(db_conn, assignment_id)
+                        .await?
+                        .filter(|assignment| {
+                            courier.is_none() || courier == Some(assignment.courier_uuid)
+                        })
+                        .ok_or_else(|| AppError::not_found_error("Assignment not found"))?;
+
+                if !is_transition_allowed(&assignment.status, new_status) {
+                    return Err(AppError::conflict_error(format!(
+                        "Cannot change assignment status from {} to {}",
+                        assignment.status, new_status
+                    )));
+                }
+
+                let now = Utc::now().naive_utc();
+                let mut new_info = UpdateAssignment {
+                    status: Some(new_status.to_string()),
+                    ..Default::default()
+                };
+                match new_status {
+                    "PICKED_UP" => new_info.picked_up_at = Some(now),
+                    "DELIVERED" => new_info.delivered_at = Some(now),
+                    "CANCELLED" => new_info.cancelled_at = Some(now),
+                    _ => {}
+                }
+                let assignment =
+                    assignments_repository::update_assignment(db_conn, assignment_id, new_info)
+                        .await?;
+
+                if matches!(new_status, "DELIVERED" | "CANCELLED") {
+                    refresh_courier_availability(db_conn, assignment.courier_uuid).await?;
+                    let event_type = match new_status {
+                        "DELIVERED" => "DELIVERED",
+                        _ => "DELIVERY_CANCELLED",
+                    };
+                    let event = AnalyticsEvent {
+                        courier_uuid: Some(assignment.courier_uuid),
+                        queue_id: assignment.queue_id,
+                        assignment_id: Some(assignment.id),
+                        duration_seconds: Some((now - assignment.assigned_at).num_seconds()),
+                        ..AnalyticsEvent::new(event_type, assignment.user_uuid, now)
+                    };
+                    record_event(db_conn, event).await?;
+                }
+                Ok(assignment)
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+// Courier is free while he can take one more delivery according to his vehicle.
+// Courier who has pending offer stays reserved untill he answers it
+pub async fn refresh_courier_availability(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<(), AppError> {
+    let active_assignments =
+        assignments_repository::count_active_courier_assignments(db_conn, courier).await?;
+    let max_deliveries = vehicles_repository::select_vehicle(db_conn, courier)
+        .await?
+        .map_or(DEFAULT_MAX_CONCURRENT_DELIVERIES, |vehicle| {
+            vehicle.max_concurrent_deliveries
+        });
+    let pending_offers = offers_repository::select_pending_courier_offers(db_conn, courier).await?;
+    let new_info = UpdateCourier {
+        is_free: Some(active_assignments < max_deliveries.into() && pending_offers.is_empty()),
+        rating: None,
+        is_on_shift: None,
+    };
+    couriers_repository::update_courier(db_conn, courier, new_info).await?;
+    Ok(())
+}
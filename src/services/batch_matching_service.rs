@@ -0,0 +1,335 @@
+use crate::models::queue_model::UserQueueInfo;
+use crate::repository::offers_repository::{select_offered_couriers, select_pending_queue_offer};
+use crate::resources::postgres::DbConn;
+use crate::services::couriers_service::expire_queue_position;
+use crate::services::matching_service::{CourierCandidate, MAX_RATING};
+use crate::services::offers_service::{create_offer, expire_timed_out_offers};
+use crate::services::queue_service;
+use crate::utils::configs::Config;
+use crate::utils::errors::{AppError, AppErrorType};
+use crate::utils::hungarian::min_cost_assignment;
+use chrono::Utc;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{error, info};
+use uuid::Uuid;
+
+// Positions taken into one batch, the solver is cubic in their number
+const MAX_BATCH_POSITIONS: usize = 200;
+// Every matched pair is cheaper than leaving position unmatched,
+// so the solver first maximizes the number of offers and only then their quality
+const MATCH_REWARD: f64 = 1000.0;
+const INFEASIBLE_COST: f64 = 1e9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DistributionMode {
+    // First position in queue is offered to the best courier for it
+    Greedy,
+    // Waiting positions and free couriers are matched together periodically
+    Batch,
+}
+
+impl FromStr for DistributionMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "greedy" => Ok(DistributionMode::Greedy),
+            "batch" => Ok(DistributionMode::Batch),
+            _ => Err(format!(
+                "Unknown distribution mode {value}. Must be one of: greedy, batch"
+            )),
+        }
+    }
+}
+
+impl Display for DistributionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DistributionMode::Greedy => "greedy",
+            DistributionMode::Batch => "batch",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct BatchCostWeights {
+    pub distance: f64,
+    pub waiting: f64,
+    pub rating: f64,
+}
+
+pub async fn batch_distribution_loop(
+    config: Config,
+    mut db_conn: DbConn<'_>,
+) -> Result<(), anyhow::Error> {
+    let mut interval = tokio::time::interval(Duration::from_secs(config.batch_interval));
+    loop {
+        interval.tick().await;
+        // Releasing couriers who haven't answered the offer in time
+        if let Err(e) = expire_timed_out_offers(&mut db_conn).await {
+            error!("Error expiring timed out offers {e}");
+        }
+        match distribute_batch(&config, &mut db_conn).await {
+            Ok(0) => {}
+            Ok(offered) => info!("Batch distribution made {offered} offers"),
+            Err(e) => error!("Error distributing batch {e}"),
+        }
+    }
+}
+
+// Matches all waiting positions with free couriers at once minimizing total cost.
+// Offers are created in one transaction, returns the number of created offers
+pub async fn distribute_batch(
+    config: &Config,
+    db_conn: &mut DbConn<'_>,
+) -> Result<usize, AppError> {
+    let now = Utc::now().naive_utc();
+    let mut positions = Vec::new();
+    for position in queue_service::select_ordered_queue(config, db_conn).await? {
+        let is_expired =
+            (now - position.created_at).num_seconds() > config.order_max_waiting_time.into();
+        if is_expired {
+            expire_queue_position(db_conn, &position).await;
+            continue;
+        }
+        // Waiting for courier's answer on current offer
+        if select_pending_queue_offer(db_conn, position.id)
+            .await?
+            .is_some()
+        {
+            continue;
+        }
+        positions.push(position);
+        if positions.len() == MAX_BATCH_POSITIONS {
+            break;
+        }
+    }
+    if positions.is_empty() {
+        return Ok(0);
+    }
+
+    // Candidates of every position, a courier can be a candidate of several ones
+    let mut couriers: HashMap<Uuid, usize> = HashMap::new();
+    let mut positions_costs: Vec<Vec<(usize, f64)>> = Vec::with_capacity(positions.len());
+    for position in &positions {
+        // Every courier gets the offer for the same position only once
+        let offered_couriers = select_offered_couriers(db_conn, position.id).await?;
+        let candidates = config
+            .matching_service
+            .select_candidates(db_conn, position, &offered_couriers)
+            .await?;
+        let waiting_seconds = (now - position.created_at).num_seconds();
+        let costs = candidates
+            .iter()
+            .map(|candidate| {
+                let next_index = couriers.len();
+                let index = *couriers
+                    .entry(candidate.courier.user_uuid)
+                    .or_insert(next_index);
+                let cost = pair_cost(
+                    config.batch_cost_weights,
+                    config.max_courier_search_radius,
+                    config.order_max_waiting_time,
+                    waiting_seconds,
+                    candidate,
+                );
+                (index, cost)
+            })
+            .collect();
+        positions_costs.push(costs);
+    }
+    if couriers.is_empty() {
+        return Ok(0);
+    }
+
+    let matrix = cost_matrix(&positions_costs, couriers.len());
+    let mut couriers_by_index = vec![Uuid::nil(); couriers.len()];
+    for (courier, index) in couriers {
+        couriers_by_index[index] = courier;
+    }
+    let pairs: Vec<(UserQueueInfo, Uuid)> = positions
+        .into_iter()
+        .zip(matched_couriers(&matrix, couriers_by_index.len()))
+        .filter_map(|(position, courier)| Some((position, couriers_by_index[courier?])))
+        .collect();
+    apply_batch(config, db_conn, pairs).await
+}
+
+// Lower is better. Every criterion is normalized to [0, 1] before weighting,
+// long waiting positions get cheaper to be served first
+fn pair_cost(
+    weights: BatchCostWeights,
+    max_search_radius: f64,
+    max_waiting_time: i32,
+    waiting_seconds: i64,
+    candidate: &CourierCandidate,
+) -> f64 {
+    let distance_penalty = candidate.distance_km.map_or(0.0, |distance| {
+        (distance / max_search_radius).clamp(0.0, 1.0)
+    });
+    let rating_penalty = (1.0 - candidate.courier.rating / MAX_RATING).clamp(0.0, 1.0);
+    let waiting_score =
+        (waiting_seconds.max(0) as f64 / f64::from(max_waiting_time.max(1))).min(1.0);
+    weights.distance * distance_penalty + weights.rating * rating_penalty
+        - weights.waiting * waiting_score
+        - MATCH_REWARD
+}
+
+// Row per position with costs of its candidates, couriers who aren't its candidates
+// are infeasible. One dummy column per position stands for leaving it unmatched
+fn cost_matrix(positions_costs: &[Vec<(usize, f64)>], couriers: usize) -> Vec<Vec<f64>> {
+    let columns = couriers + positions_costs.len();
+    positions_costs
+        .iter()
+        .map(|costs| {
+            let mut row = vec![INFEASIBLE_COST; columns];
+            row[couriers..].fill(0.0);
+            for &(courier, cost) in costs {
+                row[courier] = cost;
+            }
+            row
+        })
+        .collect()
+}
+
+// Courier chosen for every position, None if it's left unmatched
+fn matched_couriers(matrix: &[Vec<f64>], couriers: usize) -> Vec<Option<usize>> {
+    min_cost_assignment(matrix)
+        .into_iter()
+        .enumerate()
+        .map(|(row, column)| {
+            (column < couriers && matrix[row][column] < INFEASIBLE_COST).then_some(column)
+        })
+        .collect()
+}
+
+// Pairs whose position or courier has been taken meanwhile are skipped,
+// any other error rolls back the whole batch
+async fn apply_batch(
+    config: &Config,
+    db_conn: &mut DbConn<'_>,
+    pairs: Vec<(UserQueueInfo, Uuid)>,
+) -> Result<usize, AppError> {
+    let offer_timeout = config.courier_offer_timeout;
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let mut offered = 0;
+                for (position, courier) in &pairs {
+                    match create_offer(db_conn, position, *courier, offer_timeout).await {
+                        Ok(Some(_)) => offered += 1,
+                        Ok(None) => {}
+                        Err(AppError {
+                            error_type: AppErrorType::ConflictError,
+                            ..
+                        }) => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+                Ok(offered)
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::couriers_model::CourierInfo;
+
+    const WEIGHTS: BatchCostWeights = BatchCostWeights {
+        distance: 0.5,
+        waiting: 0.3,
+        rating: 0.2,
+    };
+
+    fn candidate(rating: f64, distance_km: Option<f64>) -> CourierCandidate {
+        CourierCandidate {
+            courier: CourierInfo {
+                user_uuid: Uuid::new_v4(),
+                is_free: true,
+                rating,
+            },
+            distance_km,
+            last_assigned_at: None,
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "Expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn pair_cost_combines_weighted_criteria() {
+        // Half of the radius, rating 4 of 5 and half of the maximal waiting time
+        let cost = pair_cost(WEIGHTS, 10.0, 600, 300, &candidate(4.0, Some(5.0)));
+        assert_close(cost, 0.5 * 0.5 + 0.2 * 0.2 - 0.3 * 0.5 - MATCH_REWARD);
+    }
+
+    #[test]
+    fn pair_cost_clamps_criteria() {
+        // Distance beyond the radius and waiting longer than allowed count as maximal
+        let far = pair_cost(WEIGHTS, 10.0, 600, 6000, &candidate(5.0, Some(50.0)));
+        assert_close(far, 0.5 - 0.3 - MATCH_REWARD);
+        // Unknown distance isn't penalized, clock skew can't make waiting negative
+        let unknown = pair_cost(WEIGHTS, 10.0, 600, -10, &candidate(0.0, None));
+        assert_close(unknown, 0.2 - MATCH_REWARD);
+    }
+
+    #[test]
+    fn worst_pair_is_cheaper_than_leaving_position_unmatched() {
+        let worst = pair_cost(WEIGHTS, 10.0, 600, 0, &candidate(0.0, Some(100.0)));
+        assert!(worst < 0.0);
+    }
+
+    #[test]
+    fn longer_waiting_position_is_cheaper() {
+        let courier = candidate(4.5, Some(2.0));
+        let fresh = pair_cost(WEIGHTS, 10.0, 600, 10, &courier);
+        let old = pair_cost(WEIGHTS, 10.0, 600, 500, &courier);
+        assert!(old < fresh);
+    }
+
+    #[test]
+    fn cost_matrix_marks_non_candidates_infeasible_and_adds_dummy_columns() {
+        let matrix = cost_matrix(&[vec![(1, -5.0)], vec![(0, -3.0), (1, -4.0)]], 2);
+        assert_eq!(
+            matrix,
+            vec![
+                vec![INFEASIBLE_COST, -5.0, 0.0, 0.0],
+                vec![-3.0, -4.0, 0.0, 0.0],
+            ]
+        );
+    }
+
+    #[test]
+    fn positions_without_candidates_stay_unmatched() {
+        let matrix = cost_matrix(&[vec![], vec![(0, -1000.0)]], 1);
+        assert_eq!(matched_couriers(&matrix, 1), vec![None, Some(0)]);
+    }
+
+    #[test]
+    fn competing_positions_share_couriers_optimally() {
+        // Greedy would give courier 0 to the first position and leave the second one
+        // unmatched, batch matches both
+        let positions_costs = vec![vec![(0, -1000.5), (1, -1000.4)], vec![(0, -1000.3)]];
+        let matrix = cost_matrix(&positions_costs, 2);
+        assert_eq!(matched_couriers(&matrix, 2), vec![Some(1), Some(0)]);
+    }
+
+    #[test]
+    fn extra_position_for_single_courier_is_left_unmatched() {
+        let positions_costs = vec![vec![(0, -1000.1)], vec![(0, -1000.9)]];
+        let matrix = cost_matrix(&positions_costs, 1);
+        assert_eq!(matched_couriers(&matrix, 1), vec![None, Some(0)]);
+    }
+}
@@ -1,140 +1,272 @@
-use crate::models::couriers_model::CourierInfo;
+use crate::models::analytics_model::AnalyticsEvent;
+use crate::models::outbox_model::Notification;
+use crate::models::queue_model::UserQueueInfo;
+use crate::resources::notifications::DistributionEvents;
 use crate::resources::postgres::DbConn;
-use crate::utils::grpc::orders_grpc::orders_client::OrdersClient;
+use crate::utils::backoff::Backoff;
 use crate::utils::grpc::orders_grpc::{
-    CourierForUserRequest, CourierForUserResponse, TimeExpirationRequest, TimeExpirationResponse,
+    CourierForUserRequest, CourierForUserResponse, CourierReplacedRequest, CourierReplacedResponse,
+    SearchCancelledRequest, SearchCancelledResponse, TimeExpirationRequest, TimeExpirationResponse,
 };
 use crate::{
-    models::couriers_model::UpdateCourier,
     repository::{
-        couriers_repository::{find_free_courier, update_courier},
-        queue_repository::{self, change_order_status},
+        offers_repository::{select_offered_couriers, select_pending_queue_offer},
+        queue_repository::finish_searching_position,
     },
+    services::analytics_service::record_event,
+    services::offers_service::{create_offer, expire_timed_out_offers, withdraw_queue_offer},
+    services::outbox_service::enqueue_notification,
+    services::queue_service,
     utils::configs::Config,
+    utils::errors::AppError,
 };
 use chrono::Utc;
-use std::{thread, time::Duration};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use std::time::Duration;
 use tonic::{Response, Status};
 use tracing::{error, info};
 use uuid::Uuid;
 
+// Distributor sleeps untill something changes in queue, couriers or offers.
+// Events can be missed while listener reconnects, so it never sleeps
+// longer than the fallback poll interval
 pub async fn courier_distribution_loop(
     config: Config,
     mut db_conn: DbConn<'_>,
 ) -> Result<(), anyhow::Error> {
+    let events = DistributionEvents::listen(config.database_url.clone());
+    let mut backoff = Backoff::new(
+        Duration::from_millis(config.distributor_min_backoff),
+        Duration::from_millis(config.distributor_max_backoff),
+    );
+    let poll_interval = Duration::from_secs(config.distributor_poll_interval);
     loop {
-        let queue = queue_repository::select_unfinished_queue(&mut db_conn).await;
+        // Releasing couriers who haven't answered the offer in time
+        if let Err(e) = expire_timed_out_offers(&mut db_conn).await {
+            error!("Error expiring timed out offers {e}");
+        }
+
+        let queue = queue_service::select_ordered_queue(&config, &mut db_conn).await;
         match queue {
             Err(e) => {
                 error!("Error selectig unfinished queue {e}");
+                tokio::time::sleep(backoff.next_delay()).await;
+            }
+            // Waiting for somebody to join the queue
+            Ok(queue) if queue.is_empty() => {
+                backoff.reset();
+                events.wait(poll_interval).await;
             }
-            // Wait a few seconds before next queue checking
-            // in case queue is empty
-            Ok(queue) if queue.is_empty() => thread::sleep(Duration::from_secs(2)),
             Ok(queue) => {
-                // Checking for orders with expired time and notify order service about that event
-                for user in &queue {
-                    let is_expired = (Utc::now().naive_utc() - user.created_at).num_seconds()
-                        > config.order_max_waiting_time.into();
-                    if is_expired {
-                        let status_changed =
-                            change_order_status(&mut db_conn, user.id, "EXPIRED").await;
-                        if status_changed.is_ok() {
-                            let _ = note_user_about_time_expiration(&config, user.user_uuid).await;
-                        }
-                    }
+                if offer_waiting_positions(&config, &mut db_conn, &queue).await {
+                    backoff.reset();
+                } else {
+                    // Waiting for a free courier or for an answer on current offer,
+                    // offers time out without any event so delay is bounded
+                    events.wait(backoff.next_delay().min(poll_interval)).await;
                 }
+            }
+        }
+    }
+}
 
-                // Finding courier for first person in queue and sending notification to order service
-                let first_in_queue = queue.first().expect("It cannot be empty because of previous checking");
+// Offering every waiting position in queue order, each one to a different
+// free courier, so a pending offer doesn't hold up the rest of the queue.
+// Courier is noted to order service only after accepting the offer.
+// Returns true if the queue has moved
+pub async fn offer_waiting_positions(
+    config: &Config,
+    db_conn: &mut DbConn<'_>,
+    queue: &[UserQueueInfo],
+) -> bool {
+    let mut is_moved = false;
+    for position in queue {
+        if offer_position(config, db_conn, position).await {
+            is_moved = true;
+        }
+    }
+    is_moved
+}
 
-                // searching for couriers untill find one or untill time expiration
-                'first_in_queue: loop {
-                    let is_expired = (Utc::now().naive_utc() - first_in_queue.created_at)
-                        .num_seconds()
-                        > config.order_max_waiting_time.into();
-                    if is_expired {
-                        let status_changed =
-                            change_order_status(&mut db_conn, first_in_queue.id, "EXPIRED").await;
-                        if status_changed.is_ok() {
-                            let _ = note_user_about_time_expiration(&config, first_in_queue.user_uuid).await;
-                        }
-                        break 'first_in_queue;
-                    }
+// Returns true if the position was offered or expired.
+// Offered courier isn't free anymore, so the next position gets another one
+async fn offer_position(
+    config: &Config,
+    db_conn: &mut DbConn<'_>,
+    position: &UserQueueInfo,
+) -> bool {
+    let is_expired = (Utc::now().naive_utc() - position.created_at).num_seconds()
+        > config.order_max_waiting_time.into();
+    if is_expired {
+        expire_queue_position(db_conn, position).await;
+        return true;
+    }
 
-                    let courier = find_free_courier(&mut db_conn).await;
-                    if let Ok(Some(courier)) = courier {
-                        let status_changed =
-                            change_order_status(&mut db_conn, first_in_queue.id, "COMPLETED").await;
-                        if status_changed.is_ok() {
-                            let info = UpdateCourier {
-                                is_free: Some(false),
-                                rating: None,
-                            };
-                            let courier_status_changed =
-                                update_courier(&mut db_conn, courier.user_uuid, info).await;
-                            if courier_status_changed.is_ok() {
-                                let user_noted = note_user_about_founded_courier(
-                                    &config,
-                                    first_in_queue.user_uuid,
-                                    &courier,
-                                )
-                                .await;
-                                if let Err(e) = user_noted {
-                                    error!("Error sending notification to order service, {e}");
-                                }
-                            }
-                        }
+    // Waiting for courier's answer on current offer
+    match select_pending_queue_offer(db_conn, position.id).await {
+        Ok(None) => {}
+        Ok(Some(_)) => return false,
+        Err(e) => {
+            error!("Error selecting pending offer {e}");
+            return false;
+        }
+    }
 
-                        break 'first_in_queue;
-                    }
-                }
+    loop {
+        // Every courier gets the offer for the same position only once
+        let offered_couriers = match select_offered_couriers(db_conn, position.id).await {
+            Ok(offered_couriers) => offered_couriers,
+            Err(e) => {
+                error!("Error selecting offered couriers {e}");
+                return false;
+            }
+        };
+        let courier = config
+            .matching_service
+            .find_courier(db_conn, position, &offered_couriers)
+            .await;
+        let courier = match courier {
+            Ok(Some(courier)) => courier,
+            Ok(None) => return false,
+            Err(e) => {
+                error!("Error searching for courier {e}");
+                return false;
+            }
+        };
+        let offered = create_offer(
+            db_conn,
+            position,
+            courier.user_uuid,
+            config.courier_offer_timeout,
+        )
+        .await;
+        match offered {
+            // Courier has been claimed by another distributor, trying the next one
+            Ok(None) => continue,
+            Ok(Some(_)) => return true,
+            Err(e) => {
+                error!("Error offering order to courier, {e}");
+                return false;
             }
         }
     }
 }
 
-async fn note_user_about_time_expiration(
+// Closing position in queue and its pending offer because of waiting time expiration.
+// Orders service notification is written to outbox in the same transaction
+pub async fn expire_queue_position(db_conn: &mut DbConn<'_>, position: &UserQueueInfo) {
+    let (queue_id, user_uuid, created_at) = (position.id, position.user_uuid, position.created_at);
+    let expired = db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                withdraw_queue_offer(db_conn, queue_id).await?;
+                // Position could have been cancelled by user meanwhile
+                if finish_searching_position(db_conn, queue_id, "EXPIRED").await? == 1 {
+                    let notification = Notification::SearchExpired { user_uuid };
+                    enqueue_notification(db_conn, &notification).await?;
+                    let now = Utc::now().naive_utc();
+                    let event = AnalyticsEvent {
+                        queue_id: Some(queue_id),
+                        duration_seconds: Some((now - created_at).num_seconds()),
+                        ..AnalyticsEvent::new("EXPIRED", user_uuid, now)
+                    };
+                    record_event(db_conn, event).await?;
+                }
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await;
+    if let Err(e) = expired {
+        error!("Error expiring queue position {queue_id} {e}");
+    }
+}
+
+// Notifications about search results are idempotent, Orders service
+// only tells the user, so they can be retried
+pub async fn note_user_about_time_expiration(
     config: &Config,
     uuid_user: Uuid,
 ) -> Result<Response<TimeExpirationResponse>, Status> {
-    let connected = OrdersClient::connect(config.grpc_orders_address.to_owned()).await;
-    match connected {
-        Ok(mut client) => {
-            let request = tonic::Request::new(TimeExpirationRequest {
-                user_uuid: uuid_user.to_string(),
-            });
-            Ok(client.notify_expiration_time(request).await?)
-        }
-        Err(_) => Err(Status::internal("Internal error")),
-    }
+    let request = TimeExpirationRequest {
+        user_uuid: uuid_user.to_string(),
+    };
+    config
+        .orders_grpc
+        .call(true, |mut client| {
+            let request = request.clone();
+            async move { client.notify_expiration_time(request).await }
+        })
+        .await
+}
+
+pub async fn note_user_about_search_cancellation(
+    config: &Config,
+    uuid_user: Uuid,
+    queue_id: i64,
+) -> Result<Response<SearchCancelledResponse>, Status> {
+    let request = SearchCancelledRequest {
+        user_uuid: uuid_user.to_string(),
+        queue_id,
+    };
+    config
+        .orders_grpc
+        .call(true, |mut client| {
+            let request = request.clone();
+            async move { client.notify_search_cancelled(request).await }
+        })
+        .await
 }
 
-async fn note_user_about_founded_courier(
+pub async fn note_user_about_courier_replacement(
     config: &Config,
     uuid_user: Uuid,
-    courier: &CourierInfo,
+    courier_uuid: Uuid,
+    assignment_id: i64,
+    queue_id: i64,
+) -> Result<Response<CourierReplacedResponse>, Status> {
+    let request = CourierReplacedRequest {
+        user_uuid: uuid_user.to_string(),
+        courier_uuid: courier_uuid.to_string(),
+        assignment_id,
+        queue_id,
+    };
+    config
+        .orders_grpc
+        .call(true, |mut client| {
+            let request = request.clone();
+            async move { client.notify_courier_replaced(request).await }
+        })
+        .await
+}
+
+// Orders service creates an order on this call and deduplicates it by assignment id,
+// so the call can be repeated
+pub async fn note_user_about_founded_courier(
+    config: &Config,
+    uuid_user: Uuid,
+    courier_uuid: Uuid,
+    courier_rating: f64,
+    assignment_id: i64,
 ) -> Result<Response<CourierForUserResponse>, Status> {
-    let connected = OrdersClient::connect(config.grpc_analytics_address.to_owned()).await;
-    match connected {
-        Ok(mut client) => {
-            let request = tonic::Request::new(CourierForUserRequest {
-                courier_uuid: courier.user_uuid.to_string(),
-                user_uuid: uuid_user.to_string(),
-                courier_rating: courier.rating as f32,
-            });
-            Ok(client.notify_founded_courier(request).await?)
-        }
-        Err(e) => Err(Status::internal(format!("Internal error: {}", e))),
-    }
+    let request = CourierForUserRequest {
+        courier_uuid: courier_uuid.to_string(),
+        user_uuid: uuid_user.to_string(),
+        courier_rating: courier_rating as f32,
+        assignment_id,
+    };
+    config
+        .orders_grpc
+        .call(true, |mut client| {
+            let request = request.clone();
+            async move { client.notify_founded_courier(request).await }
+        })
+        .await
 }
 
 // Trying to connect to Orders gRPC server until success
 pub async fn check_grpc_connection(config: &Config) {
-    while OrdersClient::connect(config.grpc_orders_address.to_owned())
-        .await
-        .is_err()
-    {
+    while !config.orders_grpc.is_reachable().await {
         info!("Cannot connect to Orders gRPC server. Trying to reconnect ...");
         tokio::time::sleep(Duration::from_secs(1)).await;
     }
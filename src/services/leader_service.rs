@@ -0,0 +1,135 @@
+use crate::models::leaders_model::{LeaderStatus, ServiceLeader};
+use crate::repository::leaders_repository;
+use crate::resources::postgres::{DbConn, DbPool};
+use crate::services::batch_matching_service::{batch_distribution_loop, DistributionMode};
+use crate::services::couriers_service::courier_distribution_loop;
+use crate::utils::configs::Config;
+use crate::utils::errors::AppError;
+use chrono::Utc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+pub const DISTRIBUTOR: &str = "courier_distributor";
+// Key of the distributor's advisory lock, "courier" in ASCII
+const DISTRIBUTOR_LOCK_KEY: i64 = 0x0063_6f75_7269_6572;
+const LEADER_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+// Leader who missed this many heartbeats is shown as dead
+const MISSED_HEARTBEATS_LIMIT: i64 = 3;
+
+// Only the instance holding the advisory lock distributes couriers, the others
+// keep trying to take it. The lock is released by Postgres as soon as leader's
+// connection is closed, so another instance takes over when the leader dies
+pub async fn distributor_election_loop(
+    config: Config,
+    db_pool: DbPool,
+) -> Result<(), anyhow::Error> {
+    loop {
+        match db_pool.get().await {
+            Ok(mut lock_conn) => match lead_distribution(&config, &db_pool, &mut lock_conn).await {
+                Ok(true) => warn!(
+                    "Instance {} lost distributor leadership",
+                    config.instance_id
+                ),
+                Ok(false) => {}
+                Err(e) => error!("Error electing distributor leader {e}"),
+            },
+            Err(e) => error!("Cannot get connection for distributor election {e}"),
+        }
+        tokio::time::sleep(LEADER_HEARTBEAT_INTERVAL).await;
+    }
+}
+
+// Returns false if another instance is the leader, true once leadership is lost
+async fn lead_distribution(
+    config: &Config,
+    db_pool: &DbPool,
+    lock_conn: &mut DbConn<'_>,
+) -> Result<bool, anyhow::Error> {
+    if !leaders_repository::try_advisory_lock(lock_conn, DISTRIBUTOR_LOCK_KEY).await? {
+        return Ok(false);
+    }
+    info!("Instance {} is distributor leader", config.instance_id);
+    let distributed = distribute_as_leader(config, db_pool, lock_conn).await;
+    // Connection goes back to pool, lock must not stay with it whatever the result is
+    if let Err(e) = leaders_repository::advisory_unlock(lock_conn, DISTRIBUTOR_LOCK_KEY).await {
+        error!("Error releasing distributor lock {e}");
+    }
+    distributed.map(|()| true)
+}
+
+async fn distribute_as_leader(
+    config: &Config,
+    db_pool: &DbPool,
+    lock_conn: &mut DbConn<'_>,
+) -> Result<(), anyhow::Error> {
+    let now = Utc::now().naive_utc();
+    let leader = ServiceLeader {
+        name: DISTRIBUTOR.to_string(),
+        instance_id: config.instance_id.clone(),
+        elected_at: now,
+        heartbeat_at: now,
+    };
+    leaders_repository::upsert_leader(lock_conn, leader).await?;
+
+    let db_conn = db_pool.get().await?;
+    let distribution = async {
+        match config.distribution_mode {
+            DistributionMode::Greedy => courier_distribution_loop(config.clone(), db_conn).await,
+            DistributionMode::Batch => batch_distribution_loop(config.clone(), db_conn).await,
+        }
+    };
+    tokio::select! {
+        result = distribution => result,
+        () = keep_leadership(config, lock_conn) => Ok(()),
+    }
+}
+
+// Heartbeats through the connection holding the lock,
+// returns when the connection is broken and the lock is lost with it
+async fn keep_leadership(config: &Config, lock_conn: &mut DbConn<'_>) {
+    let mut interval = tokio::time::interval(LEADER_HEARTBEAT_INTERVAL);
+    loop {
+        interval.tick().await;
+        let now = Utc::now().naive_utc();
+        match leaders_repository::touch_leader(lock_conn, DISTRIBUTOR, &config.instance_id, now)
+            .await
+        {
+            Ok(1) => {}
+            Ok(_) => {
+                error!("Distributor leader record was taken by another instance");
+                return;
+            }
+            Err(e) => {
+                error!("Error sending distributor leader heartbeat {e}");
+                return;
+            }
+        }
+    }
+}
+
+pub async fn get_distributor_status(
+    config: &Config,
+    db_conn: &mut DbConn<'_>,
+) -> Result<LeaderStatus, AppError> {
+    let leader = leaders_repository::select_leader(db_conn, DISTRIBUTOR)
+        .await
+        .map_err(AppError::db_error)?;
+    let alive_since = Utc::now().naive_utc()
+        - chrono::Duration::seconds(
+            LEADER_HEARTBEAT_INTERVAL.as_secs() as i64 * MISSED_HEARTBEATS_LIMIT,
+        );
+    let leader_alive = leader
+        .as_ref()
+        .is_some_and(|leader| leader.heartbeat_at >= alive_since);
+    let is_leader = leader_alive
+        && leader
+            .as_ref()
+            .is_some_and(|leader| leader.instance_id == config.instance_id);
+    Ok(LeaderStatus {
+        name: DISTRIBUTOR.to_string(),
+        instance_id: config.instance_id.clone(),
+        is_leader,
+        leader_alive,
+        leader,
+    })
+}
@@ -0,0 +1,53 @@
+use crate::models::locations_model::{CourierLocation, LocationPing, UpsertCourierLocation};
+use crate::repository::{couriers_repository, locations_repository};
+use crate::resources::postgres::DbConn;
+use crate::utils::errors::AppError;
+use crate::utils::geo::is_valid_coordinates;
+use chrono::Utc;
+use uuid::Uuid;
+
+// Only the latest ping of the batch is stored, any ping counts as a heartbeat.
+// Pings older than already stored location are ignored
+pub async fn save_location_pings(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    pings: Vec<LocationPing>,
+) -> Result<Option<CourierLocation>, AppError> {
+    let now = Utc::now().naive_utc();
+    let latest_ping = pings
+        .into_iter()
+        // Device clock can be ahead of server one
+        .map(|ping| (ping.recorded_at.unwrap_or(now).min(now), ping))
+        .max_by_key(|(recorded_at, _)| *recorded_at);
+    let (recorded_at, ping) = match latest_ping {
+        Some(latest_ping) => latest_ping,
+        None => return Ok(None),
+    };
+    if !is_valid_coordinates(ping.latitude, ping.longitude) {
+        return Err(AppError::validation_error("Invalid coordinates"));
+    }
+
+    couriers_repository::touch_courier(db_conn, courier, now)
+        .await
+        .map_err(AppError::db_error)?;
+
+    let stored_location = locations_repository::select_location(db_conn, courier)
+        .await
+        .map_err(AppError::db_error)?;
+    if let Some(stored_location) = stored_location {
+        if stored_location.recorded_at >= recorded_at {
+            return Ok(Some(stored_location));
+        }
+    }
+
+    let location = UpsertCourierLocation {
+        courier_uuid: courier,
+        latitude: ping.latitude,
+        longitude: ping.longitude,
+        recorded_at,
+    };
+    let location = locations_repository::upsert_location(db_conn, location)
+        .await
+        .map_err(AppError::db_error)?;
+    Ok(Some(location))
+}
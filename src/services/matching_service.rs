@@ -0,0 +1,597 @@
+use crate::models::couriers_model::CourierInfo;
+use crate::models::queue_model::UserQueueInfo;
+use crate::models::vehicles_model::{has_spare_capacity, CourierLoad, CourierVehicle, OrderSize};
+use crate::repository::{
+    assignments_repository, couriers_repository, locations_repository, schedules_repository,
+    vehicles_repository, zones_repository,
+};
+use crate::resources::postgres::DbConn;
+use crate::utils::errors::AppError;
+use crate::utils::geo::haversine_distance_km;
+use chrono::{Duration, NaiveDateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub const MAX_RATING: f64 = 5.0;
+
+pub struct CourierCandidate {
+    pub courier: CourierInfo,
+    // Distance to the pickup point, unknown for positions without one
+    pub distance_km: Option<f64>,
+    // None for couriers who have never been assigned
+    pub last_assigned_at: Option<NaiveDateTime>,
+}
+
+// Policy of choosing one courier among free ones.
+// Candidates come in the order couriers became free
+pub trait MatchingStrategy: Send + Sync {
+    fn select(&self, candidates: &[CourierCandidate]) -> Option<usize>;
+}
+
+pub struct FirstFreeStrategy;
+
+impl MatchingStrategy for FirstFreeStrategy {
+    fn select(&self, candidates: &[CourierCandidate]) -> Option<usize> {
+        (!candidates.is_empty()).then_some(0)
+    }
+}
+
+pub struct HighestRatingStrategy;
+
+impl MatchingStrategy for HighestRatingStrategy {
+    fn select(&self, candidates: &[CourierCandidate]) -> Option<usize> {
+        best_by_score(candidates, |candidate| candidate.courier.rating)
+    }
+}
+
+// Fairness: courier who has been waiting for work the longest goes first
+pub struct LeastRecentlyAssignedStrategy;
+
+impl MatchingStrategy for LeastRecentlyAssignedStrategy {
+    fn select(&self, candidates: &[CourierCandidate]) -> Option<usize> {
+        candidates
+            .iter()
+            .enumerate()
+            .min_by_key(|(index, candidate)| (candidate.last_assigned_at, *index))
+            .map(|(index, _)| index)
+    }
+}
+
+// Falls back to the first free courier if distances are unknown
+pub struct NearestStrategy;
+
+impl MatchingStrategy for NearestStrategy {
+    fn select(&self, candidates: &[CourierCandidate]) -> Option<usize> {
+        best_by_score(candidates, |candidate| {
+            candidate
+                .distance_km
+                .map_or(f64::NEG_INFINITY, |distance| -distance)
+        })
+    }
+}
+
+// Combination of rating, fairness and distance.
+// Every criterion is normalized to [0, 1] before weighting
+pub struct WeightedStrategy {
+    pub rating_weight: f64,
+    pub fairness_weight: f64,
+    pub distance_weight: f64,
+    pub max_radius_km: f64,
+}
+
+impl MatchingStrategy for WeightedStrategy {
+    fn select(&self, candidates: &[CourierCandidate]) -> Option<usize> {
+        let now = Utc::now().naive_utc();
+        let idle_seconds = |candidate: &CourierCandidate| {
+            candidate
+                .last_assigned_at
+                .map(|assigned_at| (now - assigned_at).num_seconds().max(0) as f64)
+        };
+        let max_idle_seconds = candidates
+            .iter()
+            .filter_map(idle_seconds)
+            .fold(0.0, f64::max);
+
+        best_by_score(candidates, |candidate| {
+            let rating_score = candidate.courier.rating / MAX_RATING;
+            // Never assigned couriers are the most idle ones
+            let fairness_score = match idle_seconds(candidate) {
+                Some(idle) if max_idle_seconds > 0.0 => idle / max_idle_seconds,
+                Some(_) => 0.0,
+                None => 1.0,
+            };
+            let distance_score = candidate.distance_km.map_or(0.0, |distance| {
+                (1.0 - distance / self.max_radius_km).clamp(0.0, 1.0)
+            });
+            self.rating_weight * rating_score
+                + self.fairness_weight * fairness_score
+                + self.distance_weight * distance_score
+        })
+    }
+}
+
+// Index of candidate with the highest score, the earliest one wins ties
+fn best_by_score<F>(candidates: &[CourierCandidate], score: F) -> Option<usize>
+where
+    F: Fn(&CourierCandidate) -> f64,
+{
+    candidates
+        .iter()
+        .enumerate()
+        .map(|(index, candidate)| (index, score(candidate)))
+        .fold(
+            None,
+            |best: Option<(usize, f64)>, (index, score)| match best {
+                Some((_, best_score)) if best_score >= score => best,
+                _ => Some((index, score)),
+            },
+        )
+        .map(|(index, _)| index)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatchingStrategyKind {
+    FirstFree,
+    HighestRating,
+    LeastRecentlyAssigned,
+    Nearest,
+    Weighted,
+}
+
+impl FromStr for MatchingStrategyKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "first-free" => Ok(MatchingStrategyKind::FirstFree),
+            "highest-rating" => Ok(MatchingStrategyKind::HighestRating),
+            "least-recently-assigned" => Ok(MatchingStrategyKind::LeastRecentlyAssigned),
+            "nearest" => Ok(MatchingStrategyKind::Nearest),
+            "weighted" => Ok(MatchingStrategyKind::Weighted),
+            _ => Err(format!(
+                "Unknown matching strategy {value}. Must be one of: first-free, \
+                highest-rating, least-recently-assigned, nearest, weighted"
+            )),
+        }
+    }
+}
+
+impl Display for MatchingStrategyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MatchingStrategyKind::FirstFree => "first-free",
+            MatchingStrategyKind::HighestRating => "highest-rating",
+            MatchingStrategyKind::LeastRecentlyAssigned => "least-recently-assigned",
+            MatchingStrategyKind::Nearest => "nearest",
+            MatchingStrategyKind::Weighted => "weighted",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct MatchingWeights {
+    pub rating: f64,
+    pub fairness: f64,
+    pub distance: f64,
+}
+
+// Single entry point for choosing a courier for position in queue.
+// Used both by courier distributor and by FindCourier gRPC call
+#[derive(Clone)]
+pub struct MatchingService {
+    strategy: Arc<dyn MatchingStrategy>,
+    location_ttl: i32,
+    max_radius_km: f64,
+    zone_fallback_wait: i32,
+    strict_schedule: bool,
+}
+
+impl MatchingService {
+    pub fn new(
+        kind: MatchingStrategyKind,
+        weights: MatchingWeights,
+        location_ttl: i32,
+        max_radius_km: f64,
+        zone_fallback_wait: i32,
+        strict_schedule: bool,
+    ) -> Self {
+        let strategy: Arc<dyn MatchingStrategy> = match kind {
+            MatchingStrategyKind::FirstFree => Arc::new(FirstFreeStrategy),
+            MatchingStrategyKind::HighestRating => Arc::new(HighestRatingStrategy),
+            MatchingStrategyKind::LeastRecentlyAssigned => Arc::new(LeastRecentlyAssignedStrategy),
+            MatchingStrategyKind::Nearest => Arc::new(NearestStrategy),
+            MatchingStrategyKind::Weighted => Arc::new(WeightedStrategy {
+                rating_weight: weights.rating,
+                fairness_weight: weights.fairness,
+                distance_weight: weights.distance,
+                max_radius_km,
+            }),
+        };
+        MatchingService {
+            strategy,
+            location_ttl,
+            max_radius_km,
+            zone_fallback_wait,
+            strict_schedule,
+        }
+    }
+
+    pub async fn find_courier(
+        &self,
+        db_conn: &mut DbConn<'_>,
+        position: &UserQueueInfo,
+        excluded_couriers: &[Uuid],
+    ) -> Result<Option<CourierInfo>, AppError> {
+        let mut candidates = self
+            .select_candidates(db_conn, position, excluded_couriers)
+            .await?;
+        let chosen = self.strategy.select(&candidates);
+        Ok(chosen.map(|index| candidates.swap_remove(index).courier))
+    }
+
+    // Free couriers with their distance to the pickup point and last assignment time.
+    // For positions with pickup point only couriers with fresh location
+    // inside of the search radius are considered
+    pub async fn select_candidates(
+        &self,
+        db_conn: &mut DbConn<'_>,
+        position: &UserQueueInfo,
+        excluded_couriers: &[Uuid],
+    ) -> Result<Vec<CourierCandidate>, AppError> {
+        let couriers = couriers_repository::select_free_couriers(db_conn, excluded_couriers)
+            .await
+            .map_err(AppError::db_error)?;
+        let couriers = self
+            .filter_by_capacity(db_conn, couriers, &position.order_size())
+            .await?;
+        let couriers = self.filter_by_zone(db_conn, couriers, position).await?;
+        let couriers = self.filter_by_schedule(db_conn, couriers).await?;
+        if couriers.is_empty() {
+            return Ok(Vec::new());
+        }
+        let couriers_uuids: Vec<Uuid> = couriers.iter().map(|courier| courier.user_uuid).collect();
+
+        let last_assignments: HashMap<Uuid, Option<NaiveDateTime>> =
+            assignments_repository::select_last_assignment_times(db_conn, &couriers_uuids)
+                .await
+                .map_err(AppError::db_error)?
+                .into_iter()
+                .collect();
+
+        let distances: Option<HashMap<Uuid, f64>> = match position.pickup_point() {
+            Some(pickup_point) => {
+                let fresh_since =
+                    Utc::now().naive_utc() - Duration::seconds(self.location_ttl.into());
+                let locations = locations_repository::select_fresh_locations(
+                    db_conn,
+                    &couriers_uuids,
+                    fresh_since,
+                )
+                .await
+                .map_err(AppError::db_error)?;
+                Some(
+                    locations
+                        .into_iter()
+                        .map(|location| {
+                            let distance = haversine_distance_km(
+                                pickup_point,
+                                (location.latitude, location.longitude),
+                            );
+                            (location.courier_uuid, distance)
+                        })
+                        .filter(|(_, distance)| *distance <= self.max_radius_km)
+                        .collect(),
+                )
+            }
+            None => None,
+        };
+
+        let candidates = couriers
+            .into_iter()
+            .filter_map(|courier| {
+                let distance_km = match &distances {
+                    Some(distances) => Some(*distances.get(&courier.user_uuid)?),
+                    None => None,
+                };
+                let last_assigned_at = last_assignments.get(&courier.user_uuid).copied().flatten();
+                Some(CourierCandidate {
+                    courier,
+                    distance_km,
+                    last_assigned_at,
+                })
+            })
+            .collect();
+        Ok(candidates)
+    }
+
+    // Only couriers whose vehicle fits the order together with deliveries they already have
+    async fn filter_by_capacity(
+        &self,
+        db_conn: &mut DbConn<'_>,
+        couriers: Vec<CourierInfo>,
+        order: &OrderSize,
+    ) -> Result<Vec<CourierInfo>, AppError> {
+        if couriers.is_empty() {
+            return Ok(couriers);
+        }
+        let couriers_uuids: Vec<Uuid> = couriers.iter().map(|courier| courier.user_uuid).collect();
+        let vehicles: HashMap<Uuid, CourierVehicle> =
+            vehicles_repository::select_vehicles(db_conn, &couriers_uuids)
+                .await
+                .map_err(AppError::db_error)?
+                .into_iter()
+                .map(|vehicle| (vehicle.courier_uuid, vehicle))
+                .collect();
+        let loads: HashMap<Uuid, CourierLoad> =
+            assignments_repository::select_active_loads(db_conn, &couriers_uuids)
+                .await
+                .map_err(AppError::db_error)?
+                .into_iter()
+                .map(|load| (load.courier_uuid, load))
+                .collect();
+        Ok(retain_with_spare_capacity(
+            couriers, &vehicles, &loads, order,
+        ))
+    }
+
+    // Couriers of the position's zone, after waiting for a while couriers
+    // of neighbouring zones are taken too. Couriers without zones work everywhere
+    async fn filter_by_zone(
+        &self,
+        db_conn: &mut DbConn<'_>,
+        couriers: Vec<CourierInfo>,
+        position: &UserQueueInfo,
+    ) -> Result<Vec<CourierInfo>, AppError> {
+        let Some(zone) = position.zone_id else {
+            return Ok(couriers);
+        };
+        if couriers.is_empty() {
+            return Ok(couriers);
+        }
+        let mut allowed_zones = vec![zone];
+        let waiting_time = (Utc::now().naive_utc() - position.created_at).num_seconds();
+        if waiting_time >= self.zone_fallback_wait.into() {
+            let neighbours = zones_repository::select_zone_neighbours(db_conn, zone)
+                .await
+                .map_err(AppError::db_error)?;
+            allowed_zones.extend(neighbours);
+        }
+
+        let couriers_uuids: Vec<Uuid> = couriers.iter().map(|courier| courier.user_uuid).collect();
+        let mut couriers_zones: HashMap<Uuid, Vec<i64>> = HashMap::new();
+        for (courier, courier_zone) in
+            zones_repository::select_couriers_zones(db_conn, &couriers_uuids)
+                .await
+                .map_err(AppError::db_error)?
+        {
+            couriers_zones
+                .entry(courier)
+                .or_default()
+                .push(courier_zone);
+        }
+        Ok(retain_in_zones(couriers, &couriers_zones, &allowed_zones))
+    }
+
+    // In strict mode only couriers inside of their planned shift are matched
+    async fn filter_by_schedule(
+        &self,
+        db_conn: &mut DbConn<'_>,
+        couriers: Vec<CourierInfo>,
+    ) -> Result<Vec<CourierInfo>, AppError> {
+        if !self.strict_schedule || couriers.is_empty() {
+            return Ok(couriers);
+        }
+        let couriers_uuids: Vec<Uuid> = couriers.iter().map(|courier| courier.user_uuid).collect();
+        let planned: HashSet<Uuid> = schedules_repository::select_couriers_planned_at(
+            db_conn,
+            &couriers_uuids,
+            Utc::now().naive_utc(),
+        )
+        .await
+        .map_err(AppError::db_error)?
+        .into_iter()
+        .collect();
+        Ok(retain_planned(couriers, &planned))
+    }
+}
+
+fn retain_with_spare_capacity(
+    couriers: Vec<CourierInfo>,
+    vehicles: &HashMap<Uuid, CourierVehicle>,
+    loads: &HashMap<Uuid, CourierLoad>,
+    order: &OrderSize,
+) -> Vec<CourierInfo> {
+    couriers
+        .into_iter()
+        .filter(|courier| {
+            has_spare_capacity(
+                vehicles.get(&courier.user_uuid),
+                loads.get(&courier.user_uuid),
+                order,
+            )
+        })
+        .collect()
+}
+
+fn retain_in_zones(
+    couriers: Vec<CourierInfo>,
+    couriers_zones: &HashMap<Uuid, Vec<i64>>,
+    allowed_zones: &[i64],
+) -> Vec<CourierInfo> {
+    couriers
+        .into_iter()
+        .filter(|courier| match couriers_zones.get(&courier.user_uuid) {
+            Some(zones) => zones.iter().any(|zone| allowed_zones.contains(zone)),
+            None => true,
+        })
+        .collect()
+}
+
+fn retain_planned(couriers: Vec<CourierInfo>, planned: &HashSet<Uuid>) -> Vec<CourierInfo> {
+    couriers
+        .into_iter()
+        .filter(|courier| planned.contains(&courier.user_uuid))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(
+        rating: f64,
+        distance_km: Option<f64>,
+        last_assigned_at: Option<NaiveDateTime>,
+    ) -> CourierCandidate {
+        CourierCandidate {
+            courier: courier(rating),
+            distance_km,
+            last_assigned_at,
+        }
+    }
+
+    fn courier(rating: f64) -> CourierInfo {
+        CourierInfo {
+            user_uuid: Uuid::new_v4(),
+            is_free: true,
+            rating,
+        }
+    }
+
+    fn minutes_ago(minutes: i64) -> Option<NaiveDateTime> {
+        Some(Utc::now().naive_utc() - Duration::minutes(minutes))
+    }
+
+    fn uuids(couriers: &[CourierInfo]) -> Vec<Uuid> {
+        couriers.iter().map(|courier| courier.user_uuid).collect()
+    }
+
+    #[test]
+    fn first_free_takes_courier_who_became_free_first() {
+        let candidates = vec![
+            candidate(3.0, Some(9.0), minutes_ago(1)),
+            candidate(5.0, Some(1.0), None),
+        ];
+        assert_eq!(FirstFreeStrategy.select(&candidates), Some(0));
+        assert_eq!(FirstFreeStrategy.select(&[]), None);
+    }
+
+    #[test]
+    fn highest_rating_takes_earliest_of_best_rated() {
+        let candidates = vec![
+            candidate(4.0, None, None),
+            candidate(4.8, None, None),
+            candidate(4.8, None, None),
+        ];
+        assert_eq!(HighestRatingStrategy.select(&candidates), Some(1));
+    }
+
+    #[test]
+    fn least_recently_assigned_prefers_never_assigned_then_longest_idle() {
+        let candidates = vec![
+            candidate(5.0, None, minutes_ago(5)),
+            candidate(5.0, None, minutes_ago(30)),
+            candidate(5.0, None, None),
+            candidate(5.0, None, None),
+        ];
+        assert_eq!(LeastRecentlyAssignedStrategy.select(&candidates), Some(2));
+        assert_eq!(
+            LeastRecentlyAssignedStrategy.select(&candidates[..2]),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn nearest_takes_closest_and_falls_back_to_first_free() {
+        let candidates = vec![
+            candidate(5.0, Some(3.0), None),
+            candidate(5.0, Some(0.5), None),
+            candidate(5.0, Some(0.5), None),
+        ];
+        assert_eq!(NearestStrategy.select(&candidates), Some(1));
+        let unknown = vec![candidate(5.0, None, None), candidate(5.0, None, None)];
+        assert_eq!(NearestStrategy.select(&unknown), Some(0));
+    }
+
+    #[test]
+    fn weighted_balances_criteria_and_takes_earliest_on_tie() {
+        let strategy = WeightedStrategy {
+            rating_weight: 0.2,
+            fairness_weight: 0.3,
+            distance_weight: 0.5,
+            max_radius_km: 10.0,
+        };
+        // Nearby courier outweighs better rated one
+        let candidates = vec![
+            candidate(5.0, Some(9.0), None),
+            candidate(4.0, Some(1.0), minutes_ago(1)),
+        ];
+        assert_eq!(strategy.select(&candidates), Some(1));
+        let equal = vec![
+            candidate(4.0, Some(2.0), None),
+            candidate(4.0, Some(2.0), None),
+        ];
+        assert_eq!(strategy.select(&equal), Some(0));
+    }
+
+    #[test]
+    fn capacity_filter_rejects_full_vehicle() {
+        let couriers = vec![courier(5.0), courier(5.0)];
+        let now = Utc::now().naive_utc();
+        let vehicles: HashMap<Uuid, CourierVehicle> = couriers
+            .iter()
+            .map(|courier| {
+                let vehicle = CourierVehicle {
+                    courier_uuid: courier.user_uuid,
+                    vehicle_type: "BICYCLE".to_string(),
+                    max_weight_kg: 10.0,
+                    max_volume_liters: 40.0,
+                    max_concurrent_deliveries: 2,
+                    created_at: now,
+                    updated_at: now,
+                };
+                (courier.user_uuid, vehicle)
+            })
+            .collect();
+        let full = CourierLoad {
+            courier_uuid: couriers[0].user_uuid,
+            deliveries: 1,
+            weight_kg: Some(8.0),
+            volume_liters: Some(10.0),
+        };
+        let loads = HashMap::from([(full.courier_uuid, full)]);
+        let order = OrderSize {
+            weight_kg: Some(3.0),
+            volume_liters: Some(5.0),
+        };
+        let expected = vec![couriers[1].user_uuid];
+        let kept = retain_with_spare_capacity(couriers, &vehicles, &loads, &order);
+        assert_eq!(uuids(&kept), expected);
+    }
+
+    #[test]
+    fn zone_filter_rejects_courier_of_other_zone() {
+        let couriers = vec![courier(5.0), courier(5.0), courier(5.0)];
+        let couriers_zones = HashMap::from([
+            (couriers[0].user_uuid, vec![2]),
+            (couriers[1].user_uuid, vec![2, 1]),
+        ]);
+        // Courier without zones works everywhere
+        let expected = vec![couriers[1].user_uuid, couriers[2].user_uuid];
+        let kept = retain_in_zones(couriers, &couriers_zones, &[1]);
+        assert_eq!(uuids(&kept), expected);
+    }
+
+    #[test]
+    fn schedule_filter_rejects_courier_without_planned_shift() {
+        let couriers = vec![courier(5.0), courier(5.0)];
+        let planned = HashSet::from([couriers[1].user_uuid]);
+        let expected = vec![couriers[1].user_uuid];
+        let kept = retain_planned(couriers, &planned);
+        assert_eq!(uuids(&kept), expected);
+    }
+}
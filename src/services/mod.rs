@@ -1,3 +1,20 @@
+pub mod analytics_service;
+pub mod assignments_service;
 pub mod auth_service;
+pub mod batch_matching_service;
 pub mod couriers_service;
+pub mod leader_service;
+pub mod locations_service;
+pub mod matching_service;
+pub mod offers_service;
+pub mod onboarding_service;
+pub mod outbox_service;
+pub mod presence_service;
+pub mod queue_service;
+pub mod ratings_service;
+pub mod schedules_service;
+pub mod shifts_service;
+pub mod stats_service;
 pub mod users_service;
+pub mod vehicles_service;
+pub mod zones_service;
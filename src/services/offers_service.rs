@@ -0,0 +1,262 @@
+use crate::models::assignments_model::Assignment;
+use crate::models::offers_model::{CourierOffer, CourierOffersInfo, CreateOffer, UpdateOffer};
+use crate::models::outbox_model::Notification;
+use crate::models::queue_model::UserQueueInfo;
+use crate::repository::{couriers_repository, offers_repository, queue_repository};
+use crate::resources::postgres::DbConn;
+use crate::services::assignments_service::{assign_courier, refresh_courier_availability};
+use crate::services::outbox_service::enqueue_notification;
+use crate::utils::errors::AppError;
+use chrono::{Duration, Utc};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use uuid::Uuid;
+
+// Offering position in queue to the courier and reserving him
+// untill he answers or the offer times out.
+// Position lock and courier claim are taken in one transaction, so concurrent
+// distributors can't offer one courier or one position twice.
+// None means courier has been taken meanwhile and another one can be tried
+pub async fn create_offer(
+    db_conn: &mut DbConn<'_>,
+    queue_position: &UserQueueInfo,
+    courier: Uuid,
+    offer_timeout: i32,
+) -> Result<Option<CourierOffer>, AppError> {
+    let new_offer = CreateOffer {
+        queue_id: queue_position.id,
+        user_uuid: queue_position.user_uuid,
+        courier_uuid: courier,
+        expires_at: Utc::now().naive_utc() + Duration::seconds(offer_timeout.into()),
+    };
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                queue_repository::lock_searching_position(db_conn, new_offer.queue_id)
+                    .await?
+                    .ok_or_else(|| {
+                        AppError::conflict_error("Position isn't available for offering")
+                    })?;
+                let pending_offer =
+                    offers_repository::select_pending_queue_offer(db_conn, new_offer.queue_id)
+                        .await?;
+                if pending_offer.is_some() {
+                    return Err(AppError::conflict_error(
+                        "Position has already been offered",
+                    ));
+                }
+                let claimed = couriers_repository::claim_free_courier(db_conn, courier).await?;
+                if claimed == 0 {
+                    return Ok(None);
+                }
+                let offer = offers_repository::create_offer(db_conn, new_offer).await?;
+                Ok(Some(offer))
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+// Accepted offer completes position in queue and creates assignment.
+// Orders service notification is written to outbox in the same transaction
+pub async fn accept_offer(
+    db_conn: &mut DbConn<'_>,
+    offer_id: i64,
+    courier: Uuid,
+) -> Result<Assignment, AppError> {
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let offer = take_pending_offer(db_conn, offer_id, courier).await?;
+                if offer.expires_at < Utc::now().naive_utc() {
+                    return Err(AppError::conflict_error("Offer has expired"));
+                }
+                let new_info = UpdateOffer {
+                    status: "ACCEPTED".to_string(),
+                    responded_at: Some(Utc::now().naive_utc()),
+                };
+                let offer = offers_repository::update_offer(db_conn, offer.id, new_info).await?;
+                let assignment =
+                    assign_courier(db_conn, offer.user_uuid, courier, Some(offer.queue_id)).await?;
+                let courier_info = couriers_repository::select_courier(db_conn, courier).await?;
+                let notification = Notification::CourierFound {
+                    user_uuid: offer.user_uuid,
+                    courier_uuid: courier,
+                    courier_rating: courier_info.rating,
+                    assignment_id: assignment.id,
+                };
+                enqueue_notification(db_conn, &notification).await?;
+                Ok(assignment)
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+// Declined offer releases the courier, position in queue stays
+// in SEARCHING status and will be offered to the next courier
+pub async fn decline_offer(
+    db_conn: &mut DbConn<'_>,
+    offer_id: i64,
+    courier: Uuid,
+) -> Result<CourierOffer, AppError> {
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let offer = take_pending_offer(db_conn, offer_id, courier).await?;
+                close_offer(db_conn, offer, "DECLINED").await
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+// Closing offers that weren't answered in time.
+// Offers answered after they were selected are left as they are
+pub async fn expire_timed_out_offers(db_conn: &mut DbConn<'_>) -> Result<usize, AppError> {
+    let offers = offers_repository::select_timed_out_offers(db_conn, Utc::now().naive_utc())
+        .await
+        .map_err(AppError::db_error)?;
+    let mut expired = 0;
+    for offer in offers {
+        let closed = db_conn
+            .transaction::<_, AppError, _>(|db_conn| {
+                async move { close_pending_offer(db_conn, offer.id, "EXPIRED").await }.scope_boxed()
+            })
+            .await?;
+        if closed.is_some() {
+            expired += 1;
+        }
+    }
+    Ok(expired)
+}
+
+// Closing pending offer of position which isn't waiting for courier anymore
+pub async fn withdraw_queue_offer(db_conn: &mut DbConn<'_>, queue_id: i64) -> Result<(), AppError> {
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let offer =
+                    offers_repository::select_pending_queue_offer(db_conn, queue_id).await?;
+                if let Some(offer) = offer {
+                    close_pending_offer(db_conn, offer.id, "EXPIRED").await?;
+                }
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+// Closing pending offer of position cancelled by user.
+// Offer is locked before the position, the same way accepting does
+pub async fn withdraw_cancelled_queue_offer(
+    db_conn: &mut DbConn<'_>,
+    queue_id: i64,
+) -> Result<Option<CourierOffer>, AppError> {
+    let offer = offers_repository::select_pending_queue_offer(db_conn, queue_id).await?;
+    match offer {
+        Some(offer) => close_pending_offer(db_conn, offer.id, "WITHDRAWN").await,
+        None => Ok(None),
+    }
+}
+
+// Closing all offers courier hasn't answered yet, positions in queue stay in SEARCHING status.
+// Must be called inside of transaction
+pub async fn expire_courier_offers(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<usize, AppError> {
+    let offers = offers_repository::select_pending_courier_offers(db_conn, courier).await?;
+    let mut expired = 0;
+    for offer in offers {
+        let closed = close_pending_offer(db_conn, offer.id, "EXPIRED").await?;
+        if closed.is_some() {
+            expired += 1;
+        }
+    }
+    Ok(expired)
+}
+
+pub async fn get_courier_offers(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<CourierOffersInfo, AppError> {
+    let offers = offers_repository::select_pending_courier_offers(db_conn, courier)
+        .await
+        .map_err(AppError::db_error)?;
+    let acceptance_rate = count_acceptance_rate(db_conn, courier).await?;
+    Ok(CourierOffersInfo {
+        offers,
+        acceptance_rate,
+    })
+}
+
+// Share of answered offers which were accepted.
+// Couriers without answered offers have rate 1.0
+pub async fn count_acceptance_rate(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<f64, AppError> {
+    let mut answered = 0;
+    let mut accepted = 0;
+    for status in ["ACCEPTED", "DECLINED", "EXPIRED"] {
+        let count = offers_repository::count_courier_offers_by_status(db_conn, courier, status)
+            .await
+            .map_err(AppError::db_error)?;
+        if status == "ACCEPTED" {
+            accepted = count;
+        }
+        answered += count;
+    }
+    if answered == 0 {
+        return Ok(1.0);
+    }
+    Ok(accepted as f64 / answered as f64)
+}
+
+async fn take_pending_offer(
+    db_conn: &mut DbConn<'_>,
+    offer_id: i64,
+    courier: Uuid,
+) -> Result<CourierOffer, AppError> {
+    let offer = offers_repository::select_offer_for_update(db_conn, offer_id)
+        .await?
+        .filter(|offer| offer.courier_uuid == courier)
+        .ok_or_else(|| AppError::not_found_error("Offer not found"))?;
+    if offer.status != "PENDING" {
+        return Err(AppError::conflict_error(format!(
+            "Offer is already {}",
+            offer.status.to_lowercase()
+        )));
+    }
+    Ok(offer)
+}
+
+// Offer is locked and closed only if it's still pending, so an answer given
+// after the offer was selected isn't overwritten. Must be called inside of transaction
+async fn close_pending_offer(
+    db_conn: &mut DbConn<'_>,
+    offer_id: i64,
+    new_status: &str,
+) -> Result<Option<CourierOffer>, AppError> {
+    match offers_repository::select_offer_for_update(db_conn, offer_id).await? {
+        Some(offer) if offer.status == "PENDING" => {
+            Ok(Some(close_offer(db_conn, offer, new_status).await?))
+        }
+        _ => Ok(None),
+    }
+}
+
+async fn close_offer(
+    db_conn: &mut DbConn<'_>,
+    offer: CourierOffer,
+    new_status: &str,
+) -> Result<CourierOffer, AppError> {
+    let new_info = UpdateOffer {
+        status: new_status.to_string(),
+        responded_at: Some(Utc::now().naive_utc()),
+    };
+    let offer = offers_repository::update_offer(db_conn, offer.id, new_info).await?;
+    refresh_courier_availability(db_conn, offer.courier_uuid).await?;
+    Ok(offer)
+}
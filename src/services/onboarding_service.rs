@@ -0,0 +1,269 @@
+use crate::models::couriers_model::{CourierOnboarding, UpdateOnboardingStatus};
+use crate::models::documents_model::{
+    CourierDocument, DocumentType, OnboardingInfo, UpsertCourierDocument, ALLOWED_CONTENT_TYPES,
+};
+use crate::repository::{couriers_repository, documents_repository, vehicles_repository};
+use crate::resources::documents_storage::DocumentsStorage;
+use crate::resources::postgres::DbConn;
+use crate::utils::errors::AppError;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use tracing::error;
+use uuid::Uuid;
+
+pub const ONBOARDING_STATUSES: [&str; 4] =
+    ["PENDING_DOCUMENTS", "UNDER_REVIEW", "APPROVED", "REJECTED"];
+// Statuses in which courier can change his documents and send them for review
+const EDITABLE_STATUSES: [&str; 2] = ["PENDING_DOCUMENTS", "REJECTED"];
+
+// File is saved under a new key every time, so the previous one
+// is removed only after the database points to the new file
+pub async fn upload_document(
+    storage: &dyn DocumentsStorage,
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    document_type: DocumentType,
+    content_type: &str,
+    content: &[u8],
+) -> Result<CourierDocument, AppError> {
+    if content.is_empty() {
+        return Err(AppError::validation_error("Document is empty"));
+    }
+    if !ALLOWED_CONTENT_TYPES.contains(&content_type) {
+        return Err(AppError::validation_error(format!(
+            "Unsupported content type {content_type}. Must be one of: {}",
+            ALLOWED_CONTENT_TYPES.join(", ")
+        )));
+    }
+    let onboarding = select_onboarding(db_conn, courier).await?;
+    check_editable(&onboarding)?;
+
+    let storage_key = format!("{courier}/{document_type}-{}", Uuid::new_v4());
+    storage
+        .save(&storage_key, content)
+        .await
+        .map_err(AppError::storage_error)?;
+
+    let new_document = UpsertCourierDocument {
+        courier_uuid: courier,
+        document_type: document_type.to_string(),
+        content_type: content_type.to_string(),
+        size_bytes: content.len() as i64,
+        storage_key: storage_key.clone(),
+    };
+    let saved = db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let onboarding =
+                    couriers_repository::select_courier_onboarding_for_update(db_conn, courier)
+                        .await?
+                        .ok_or_else(|| AppError::not_found_error("Courier not found"))?;
+                check_editable(&onboarding)?;
+                let previous = documents_repository::select_courier_document_by_type(
+                    db_conn,
+                    courier,
+                    &new_document.document_type,
+                )
+                .await?;
+                let document = documents_repository::upsert_document(db_conn, new_document).await?;
+                Ok((document, previous))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+    match saved {
+        Ok((document, previous)) => {
+            if let Some(previous) = previous {
+                remove_file(storage, &previous.storage_key).await;
+            }
+            Ok(document)
+        }
+        Err(e) => {
+            remove_file(storage, &storage_key).await;
+            Err(e)
+        }
+    }
+}
+
+// Courier sends uploaded documents to admins.
+// Driving license is required only for motor vehicles
+pub async fn submit_for_review(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<OnboardingInfo, AppError> {
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let onboarding =
+                    couriers_repository::select_courier_onboarding_for_update(db_conn, courier)
+                        .await?
+                        .ok_or_else(|| AppError::not_found_error("Courier not found"))?;
+                check_editable(&onboarding)?;
+
+                let mut required_documents = vec![DocumentType::Id];
+                let vehicle = vehicles_repository::select_vehicle(db_conn, courier).await?;
+                if vehicle.is_some_and(|vehicle| {
+                    matches!(vehicle.vehicle_type.as_str(), "SCOOTER" | "CAR")
+                }) {
+                    required_documents.push(DocumentType::DrivingLicense);
+                }
+                let documents =
+                    documents_repository::select_courier_documents(db_conn, courier).await?;
+                let missing_documents: Vec<String> = required_documents
+                    .iter()
+                    .map(DocumentType::to_string)
+                    .filter(|required| {
+                        !documents
+                            .iter()
+                            .any(|document| &document.document_type == required)
+                    })
+                    .collect();
+                if !missing_documents.is_empty() {
+                    return Err(AppError::validation_error(format!(
+                        "Missing documents: {}",
+                        missing_documents.join(", ")
+                    )));
+                }
+
+                let new_status = UpdateOnboardingStatus {
+                    onboarding_status: "UNDER_REVIEW".to_string(),
+                    rejection_reason: None,
+                };
+                couriers_repository::update_onboarding_status(db_conn, courier, new_status).await?;
+                Ok(OnboardingInfo {
+                    onboarding_status: "UNDER_REVIEW".to_string(),
+                    rejection_reason: None,
+                    documents,
+                })
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+pub async fn approve_courier(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<OnboardingInfo, AppError> {
+    review_courier(db_conn, courier, "APPROVED", None).await
+}
+
+pub async fn reject_courier(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    reason: String,
+) -> Result<OnboardingInfo, AppError> {
+    review_courier(db_conn, courier, "REJECTED", Some(reason)).await
+}
+
+pub async fn get_onboarding_info(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<OnboardingInfo, AppError> {
+    let onboarding = select_onboarding(db_conn, courier).await?;
+    let documents = documents_repository::select_courier_documents(db_conn, courier)
+        .await
+        .map_err(AppError::db_error)?;
+    Ok(OnboardingInfo {
+        onboarding_status: onboarding.onboarding_status,
+        rejection_reason: onboarding.rejection_reason,
+        documents,
+    })
+}
+
+// Couriers waiting for review by default, the ones who applied earlier go first
+pub async fn get_couriers_by_onboarding_status(
+    db_conn: &mut DbConn<'_>,
+    status: Option<String>,
+) -> Result<Vec<CourierOnboarding>, AppError> {
+    let status = status.unwrap_or_else(|| "UNDER_REVIEW".to_string());
+    if !ONBOARDING_STATUSES.contains(&status.as_str()) {
+        return Err(AppError::validation_error(format!(
+            "Unknown onboarding status {status}. Must be one of: {}",
+            ONBOARDING_STATUSES.join(", ")
+        )));
+    }
+    couriers_repository::select_couriers_by_onboarding_status(db_conn, &status)
+        .await
+        .map_err(AppError::db_error)
+}
+
+pub async fn get_document_content(
+    storage: &dyn DocumentsStorage,
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    document_id: i64,
+) -> Result<(CourierDocument, Vec<u8>), AppError> {
+    let document = documents_repository::select_courier_document(db_conn, courier, document_id)
+        .await
+        .map_err(AppError::db_error)?
+        .ok_or_else(|| AppError::not_found_error("Document not found"))?;
+    let content = storage
+        .load(&document.storage_key)
+        .await
+        .map_err(AppError::storage_error)?;
+    Ok((document, content))
+}
+
+async fn review_courier(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    new_status: &'static str,
+    rejection_reason: Option<String>,
+) -> Result<OnboardingInfo, AppError> {
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let onboarding =
+                    couriers_repository::select_courier_onboarding_for_update(db_conn, courier)
+                        .await?
+                        .ok_or_else(|| AppError::not_found_error("Courier not found"))?;
+                if onboarding.onboarding_status != "UNDER_REVIEW" {
+                    return Err(AppError::conflict_error(format!(
+                        "Courier is not under review, current status is {}",
+                        onboarding.onboarding_status
+                    )));
+                }
+                let new_info = UpdateOnboardingStatus {
+                    onboarding_status: new_status.to_string(),
+                    rejection_reason: rejection_reason.clone(),
+                };
+                couriers_repository::update_onboarding_status(db_conn, courier, new_info).await?;
+                let documents =
+                    documents_repository::select_courier_documents(db_conn, courier).await?;
+                Ok(OnboardingInfo {
+                    onboarding_status: new_status.to_string(),
+                    rejection_reason,
+                    documents,
+                })
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+async fn select_onboarding(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<CourierOnboarding, AppError> {
+    couriers_repository::select_courier_onboarding(db_conn, courier)
+        .await
+        .map_err(AppError::db_error)?
+        .ok_or_else(|| AppError::not_found_error("Courier not found"))
+}
+
+fn check_editable(onboarding: &CourierOnboarding) -> Result<(), AppError> {
+    if !EDITABLE_STATUSES.contains(&onboarding.onboarding_status.as_str()) {
+        return Err(AppError::conflict_error(format!(
+            "Documents cannot be changed in {} status",
+            onboarding.onboarding_status
+        )));
+    }
+    Ok(())
+}
+
+async fn remove_file(storage: &dyn DocumentsStorage, key: &str) {
+    if let Err(e) = storage.delete(key).await {
+        error!("Error removing document {key} from storage {e}");
+    }
+}
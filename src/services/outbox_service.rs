@@ -0,0 +1,323 @@
+use crate::models::outbox_model::{
+    CreateOutboxMessage, Notification, OutboxMessage, OutboxMessagesPage, OutboxMessagesQuery,
+    UpdateOutboxMessage,
+};
+use crate::repository::outbox_repository;
+use crate::resources::postgres::{DbConn, DbPool};
+use crate::services::analytics_service::send_delivery_events;
+use crate::services::couriers_service::{
+    note_user_about_courier_replacement, note_user_about_founded_courier,
+    note_user_about_search_cancellation, note_user_about_time_expiration,
+};
+use crate::services::users_service::send_reg_info_to_analytics_service;
+use crate::utils::backoff::retry_delay;
+use crate::utils::configs::Config;
+use crate::utils::errors::AppError;
+use chrono::Utc;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use std::time::Duration;
+use tracing::{error, info, warn};
+use validator::Validate;
+
+// Messages claimed by one dispatcher run
+const OUTBOX_BATCH_SIZE: i64 = 50;
+const ANALYTICS_EVENT_KIND: &str = "ANALYTICS_EVENT";
+
+// Must be called inside of the transaction changing the state notification is about,
+// so the notification is sent if and only if the change is committed
+pub async fn enqueue_notification(
+    db_conn: &mut DbConn<'_>,
+    notification: &Notification,
+) -> Result<(), AppError> {
+    let message = CreateOutboxMessage {
+        kind: notification.kind().to_string(),
+        payload: serde_json::to_value(notification).map_err(AppError::serde_error)?,
+    };
+    outbox_repository::insert_outbox_message(db_conn, message).await?;
+    Ok(())
+}
+
+// Periodically delivering due outbox messages. Failed deliveries are retried
+// with exponential back-off, after the last attempt message is marked dead
+pub async fn outbox_dispatch_loop(config: Config, db_pool: DbPool) -> Result<(), anyhow::Error> {
+    let mut interval = tokio::time::interval(Duration::from_secs(config.outbox_poll_interval));
+    loop {
+        interval.tick().await;
+        let mut db_conn = match db_pool.get().await {
+            Ok(db_conn) => db_conn,
+            Err(e) => {
+                error!("Cannot get connection for outbox dispatcher {e}");
+                continue;
+            }
+        };
+        match dispatch_due_messages(&config, &mut db_conn).await {
+            Ok(0) => {}
+            Ok(handled) => info!("Outbox dispatcher handled {handled} messages"),
+            Err(e) => error!("Error dispatching outbox messages {e}"),
+        }
+    }
+}
+
+// Due messages are claimed in a short transaction and delivered outside of it,
+// so several instances can run dispatcher without sending a message twice
+// and without keeping the transaction open while other services answer
+pub async fn dispatch_due_messages(
+    config: &Config,
+    db_conn: &mut DbConn<'_>,
+) -> Result<usize, AppError> {
+    let messages = claim_due_messages(config, db_conn).await?;
+    let handled = messages.len();
+    let (events, notifications): (Vec<_>, Vec<_>) = messages
+        .into_iter()
+        .partition(|message| message.kind == ANALYTICS_EVENT_KIND);
+    for message in notifications {
+        let delivered = deliver(config, &message).await;
+        record_outcome(config, db_conn, &message, delivered).await;
+    }
+    for (message, delivered) in deliver_events(config, events).await {
+        record_outcome(config, db_conn, &message, delivered).await;
+    }
+    Ok(handled)
+}
+
+// Messages of a dispatcher stopped before recording the outcome
+// are claimed again when their lease expires
+async fn claim_due_messages(
+    config: &Config,
+    db_conn: &mut DbConn<'_>,
+) -> Result<Vec<OutboxMessage>, AppError> {
+    let now = Utc::now().naive_utc();
+    let lease_until = now + chrono::Duration::seconds(config.outbox_lease_timeout.into());
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let messages =
+                    outbox_repository::lock_due_messages(db_conn, now, OUTBOX_BATCH_SIZE).await?;
+                let message_ids: Vec<i64> = messages.iter().map(|message| message.id).collect();
+                let mut claimed =
+                    outbox_repository::claim_outbox_messages(db_conn, &message_ids, lease_until)
+                        .await?;
+                // Notifications about one user are delivered in the order they were made
+                claimed.sort_by_key(|message| message.id);
+                Ok(claimed)
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+// Every outcome is saved on its own, failure to save one doesn't affect the others
+async fn record_outcome(
+    config: &Config,
+    db_conn: &mut DbConn<'_>,
+    message: &OutboxMessage,
+    delivered: Result<(), String>,
+) {
+    let new_info = delivery_outcome(config, message, delivered);
+    match outbox_repository::update_claimed_message(
+        db_conn,
+        message.id,
+        message.next_attempt_at,
+        new_info,
+    )
+    .await
+    {
+        Ok(0) => warn!(
+            "Lease of outbox message {} expired before its outcome was recorded",
+            message.id
+        ),
+        Ok(_) => {}
+        Err(e) => error!("Cannot record outcome of outbox message {} {e}", message.id),
+    }
+}
+
+// Analytics events are sent in one call, its result is the result of every event in it.
+// Events that can't be read fail on their own
+async fn deliver_events(
+    config: &Config,
+    messages: Vec<OutboxMessage>,
+) -> Vec<(OutboxMessage, Result<(), String>)> {
+    let mut outcomes = Vec::with_capacity(messages.len());
+    let mut batch = Vec::new();
+    let mut batch_messages = Vec::new();
+    for message in messages {
+        match serde_json::from_value(message.payload.clone()) {
+            Ok(Notification::AnalyticsEvent(event)) => {
+                batch.push((message.id, event));
+                batch_messages.push(message);
+            }
+            Ok(_) => outcomes.push((message, Err("Not an analytics event".to_string()))),
+            Err(e) => outcomes.push((message, Err(e.to_string()))),
+        }
+    }
+    if batch.is_empty() {
+        return outcomes;
+    }
+    let delivered = send_delivery_events(config, batch)
+        .await
+        .map(drop)
+        .map_err(|status| status.to_string());
+    outcomes.extend(
+        batch_messages
+            .into_iter()
+            .map(|message| (message, delivered.clone())),
+    );
+    outcomes
+}
+
+async fn deliver(config: &Config, message: &OutboxMessage) -> Result<(), String> {
+    let notification: Notification =
+        serde_json::from_value(message.payload.clone()).map_err(|e| e.to_string())?;
+    let delivered = match notification {
+        Notification::CourierFound {
+            user_uuid,
+            courier_uuid,
+            courier_rating,
+            assignment_id,
+        } => note_user_about_founded_courier(
+            config,
+            user_uuid,
+            courier_uuid,
+            courier_rating,
+            assignment_id,
+        )
+        .await
+        .map(drop),
+        Notification::SearchExpired { user_uuid } => {
+            note_user_about_time_expiration(config, user_uuid)
+                .await
+                .map(drop)
+        }
+        Notification::SearchCancelled {
+            user_uuid,
+            queue_id,
+        } => note_user_about_search_cancellation(config, user_uuid, queue_id)
+            .await
+            .map(drop),
+        Notification::CourierReplaced {
+            user_uuid,
+            courier_uuid,
+            assignment_id,
+            queue_id,
+        } => note_user_about_courier_replacement(
+            config,
+            user_uuid,
+            courier_uuid,
+            assignment_id,
+            queue_id,
+        )
+        .await
+        .map(drop),
+        Notification::Registered {
+            uuid,
+            role,
+            created_at,
+        } => send_reg_info_to_analytics_service(config, uuid, &role, created_at)
+            .await
+            .map(drop),
+        Notification::AnalyticsEvent(event) => {
+            send_delivery_events(config, vec![(message.id, event)])
+                .await
+                .map(drop)
+        }
+    };
+    delivered.map_err(|status| status.to_string())
+}
+
+fn delivery_outcome(
+    config: &Config,
+    message: &OutboxMessage,
+    delivered: Result<(), String>,
+) -> UpdateOutboxMessage {
+    let now = Utc::now().naive_utc();
+    let attempts = message.attempts + 1;
+    match delivered {
+        Ok(()) => UpdateOutboxMessage {
+            status: "DELIVERED".to_string(),
+            attempts,
+            last_error: None,
+            next_attempt_at: message.next_attempt_at,
+            delivered_at: Some(now),
+        },
+        Err(e) if attempts >= config.outbox_max_attempts => {
+            error!(
+                "Outbox message {} of kind {} is dead after {attempts} attempts, {e}",
+                message.id, message.kind
+            );
+            UpdateOutboxMessage {
+                status: "DEAD".to_string(),
+                attempts,
+                last_error: Some(e),
+                next_attempt_at: message.next_attempt_at,
+                delivered_at: None,
+            }
+        }
+        Err(e) => {
+            warn!(
+                "Error delivering outbox message {} of kind {}, {e}",
+                message.id, message.kind
+            );
+            let delay = retry_delay(
+                Duration::from_secs(config.outbox_min_backoff),
+                Duration::from_secs(config.outbox_max_backoff),
+                attempts as u32,
+            );
+            UpdateOutboxMessage {
+                status: "PENDING".to_string(),
+                attempts,
+                last_error: Some(e),
+                next_attempt_at: now
+                    + chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::zero()),
+                delivered_at: None,
+            }
+        }
+    }
+}
+
+pub async fn get_outbox_messages(
+    db_conn: &mut DbConn<'_>,
+    query: OutboxMessagesQuery,
+) -> Result<OutboxMessagesPage, AppError> {
+    query
+        .validate()
+        .map_err(|e| AppError::validation_error(e.to_string()))?;
+    let offset = (query.page - 1) * query.limit;
+    let status = query.status.as_deref();
+    let messages =
+        outbox_repository::select_outbox_messages(db_conn, status, offset, query.limit).await?;
+    let total = outbox_repository::count_outbox_messages(db_conn, status).await?;
+    Ok(OutboxMessagesPage {
+        messages,
+        total,
+        page: query.page,
+        limit: query.limit,
+    })
+}
+
+pub async fn get_outbox_message(
+    db_conn: &mut DbConn<'_>,
+    message_id: i64,
+) -> Result<OutboxMessage, AppError> {
+    outbox_repository::select_outbox_message(db_conn, message_id)
+        .await?
+        .ok_or_else(|| AppError::not_found_error("Outbox message not found"))
+}
+
+// Sending delivered or dead message once again, it gets a fresh set of attempts
+pub async fn replay_outbox_message(
+    db_conn: &mut DbConn<'_>,
+    message_id: i64,
+) -> Result<OutboxMessage, AppError> {
+    let replayed =
+        outbox_repository::replay_outbox_message(db_conn, message_id, Utc::now().naive_utc())
+            .await?;
+    match replayed {
+        Some(message) => Ok(message),
+        None => {
+            get_outbox_message(db_conn, message_id).await?;
+            Err(AppError::conflict_error(
+                "Outbox message is already waiting for delivery",
+            ))
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::models::couriers_model::CourierHeartbeat;
+use crate::models::outbox_model::Notification;
+use crate::models::queue_model::{AddUserToQueue, MAX_QUEUE_PRIORITY};
+use crate::repository::{
+    assignments_repository, couriers_repository, queue_repository, shifts_repository,
+};
+use crate::resources::postgres::{DbConn, DbPool};
+use crate::services::outbox_service::enqueue_notification;
+use crate::services::{assignments_service, offers_service, queue_service, shifts_service};
+use crate::utils::configs::Config;
+use crate::utils::errors::AppError;
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+// Any sign of life from courier's app: heartbeat itself, location ping or shift start
+pub async fn heartbeat(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<CourierHeartbeat, AppError> {
+    couriers_repository::touch_courier(db_conn, courier, Utc::now().naive_utc())
+        .await
+        .map_err(AppError::db_error)?
+        .ok_or_else(|| AppError::not_found_error("Courier not found"))
+}
+
+// Periodically taking offline couriers who have been silent for too long
+pub async fn courier_sweeper_loop(config: Config, db_pool: DbPool) -> Result<(), anyhow::Error> {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+        config.courier_sweep_interval,
+    ));
+    loop {
+        interval.tick().await;
+        let mut db_conn = match db_pool.get().await {
+            Ok(db_conn) => db_conn,
+            Err(e) => {
+                error!("Cannot get connection for courier sweeper {e}");
+                continue;
+            }
+        };
+        let silent_since =
+            Utc::now().naive_utc() - Duration::seconds(config.courier_heartbeat_timeout.into());
+        let couriers =
+            match couriers_repository::select_silent_couriers(&mut db_conn, silent_since).await {
+                Ok(couriers) => couriers,
+                Err(e) => {
+                    error!("Error selecting silent couriers {e}");
+                    continue;
+                }
+            };
+        for courier in couriers {
+            match mark_courier_offline(&mut db_conn, courier, silent_since).await {
+                Ok(true) => info!("Courier {courier} is offline, shift is paused"),
+                // Heartbeat has come meanwhile or another sweeper has done it
+                Ok(false) => {}
+                Err(e) => error!("Error marking courier {courier} offline {e}"),
+            }
+        }
+    }
+}
+
+// Pausing courier's shift and giving his work to other couriers.
+// Orders which are already picked up stay with the courier.
+// Orders service is told about replaced courier through outbox.
+// Returns false if courier isn't silent since `silent_since` anymore
+pub async fn mark_courier_offline(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    silent_since: NaiveDateTime,
+) -> Result<bool, AppError> {
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let silent =
+                    couriers_repository::lock_silent_courier(db_conn, courier, silent_since)
+                        .await?;
+                if silent.is_none() {
+                    return Ok(false);
+                }
+                let open_shift = shifts_repository::select_open_shift(db_conn, courier).await?;
+                if open_shift.is_some_and(|shift| shift.status == "ACTIVE") {
+                    shifts_service::pause_shift(db_conn, courier).await?;
+                } else {
+                    // Flag left without active shift, otherwise he is swept again every time
+                    shifts_service::set_courier_on_shift(db_conn, courier, false).await?;
+                }
+
+                offers_service::expire_courier_offers(db_conn, courier).await?;
+
+                let assignments =
+                    assignments_repository::select_active_courier_assignments(db_conn, courier)
+                        .await?;
+                for assignment in assignments {
+                    if assignment.status != "ASSIGNED" {
+                        warn!(
+                            "Courier {courier} went offline with picked up assignment {}",
+                            assignment.id
+                        );
+                        continue;
+                    }
+                    assignments_service::cancel_assignment(db_conn, assignment.id, Some(courier))
+                        .await?;
+                    let Some(queue_id) = assignment.queue_id else {
+                        continue;
+                    };
+                    // User gets new position in queue with the same order
+                    let position = queue_repository::select_queue_position(db_conn, queue_id)
+                        .await?
+                        .ok_or_else(|| AppError::not_found_error("Queue position not found"))?;
+                    let new_position = AddUserToQueue {
+                        user_uuid: position.user_uuid,
+                        pickup_latitude: position.pickup_latitude,
+                        pickup_longitude: position.pickup_longitude,
+                        order_weight_kg: position.order_weight_kg,
+                        order_volume_liters: position.order_volume_liters,
+                        zone_id: position.zone_id,
+                        // Retried position shouldn't wait behind everyone again
+                        priority: (position.priority + 1).min(MAX_QUEUE_PRIORITY),
+                    };
+                    let new_position =
+                        queue_service::add_queue_position(db_conn, new_position).await?;
+                    let notification = Notification::CourierReplaced {
+                        user_uuid: position.user_uuid,
+                        courier_uuid: courier,
+                        assignment_id: assignment.id,
+                        queue_id: new_position.id,
+                    };
+                    enqueue_notification(db_conn, &notification).await?;
+                }
+                Ok(true)
+            }
+            .scope_boxed()
+        })
+        .await
+}
@@ -0,0 +1,91 @@
+use crate::models::analytics_model::AnalyticsEvent;
+use crate::models::outbox_model::Notification;
+use crate::models::queue_model::{AddUserToQueue, UserQueueInfo};
+use crate::repository::queue_repository;
+use crate::resources::postgres::DbConn;
+use crate::services::analytics_service::record_event;
+use crate::services::offers_service::withdraw_cancelled_queue_offer;
+use crate::services::outbox_service::enqueue_notification;
+use crate::utils::configs::Config;
+use crate::utils::errors::AppError;
+use chrono::Utc;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use uuid::Uuid;
+
+// Searching positions in the order they are offered to couriers
+pub async fn select_ordered_queue(
+    config: &Config,
+    db_conn: &mut DbConn<'_>,
+) -> Result<Vec<UserQueueInfo>, AppError> {
+    let mut queue = queue_repository::select_unfinished_queue(db_conn)
+        .await
+        .map_err(AppError::db_error)?;
+    queue.sort_by_key(|position| position.queue_key(config.queue_priority_step));
+    Ok(queue)
+}
+
+pub async fn add_queue_position(
+    db_conn: &mut DbConn<'_>,
+    new_position: AddUserToQueue,
+) -> Result<UserQueueInfo, AppError> {
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let position = queue_repository::add_user_to_queue(db_conn, new_position).await?;
+                let event = AnalyticsEvent {
+                    queue_id: Some(position.id),
+                    ..AnalyticsEvent::new("QUEUED", position.user_uuid, position.created_at)
+                };
+                record_event(db_conn, event).await?;
+                Ok(position)
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+// Cancelling user's search and releasing courier who has been offered the position.
+// Orders service notification is written to outbox in the same transaction
+pub async fn cancel_courier_search(
+    db_conn: &mut DbConn<'_>,
+    user: Uuid,
+) -> Result<UserQueueInfo, AppError> {
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let position = queue_repository::select_searching_position(db_conn, user)
+                    .await?
+                    .ok_or_else(|| {
+                        AppError::not_found_error("User isn't searching for a courier")
+                    })?;
+                withdraw_cancelled_queue_offer(db_conn, position.id).await?;
+                let cancelled =
+                    queue_repository::finish_searching_position(db_conn, position.id, "CANCELED")
+                        .await?;
+                // Courier has accepted the offer or search has expired meanwhile
+                if cancelled == 0 {
+                    return Err(AppError::conflict_error(
+                        "Search is already finished and can't be cancelled",
+                    ));
+                }
+                let notification = Notification::SearchCancelled {
+                    user_uuid: user,
+                    queue_id: position.id,
+                };
+                enqueue_notification(db_conn, &notification).await?;
+                let now = Utc::now().naive_utc();
+                let event = AnalyticsEvent {
+                    queue_id: Some(position.id),
+                    duration_seconds: Some((now - position.created_at).num_seconds()),
+                    ..AnalyticsEvent::new("CANCELLED", user, now)
+                };
+                record_event(db_conn, event).await?;
+                Ok(UserQueueInfo {
+                    status: "CANCELED".to_string(),
+                    ..position
+                })
+            }
+            .scope_boxed()
+        })
+        .await
+}
@@ -0,0 +1,159 @@
+use crate::models::analytics_model::AnalyticsEvent;
+use crate::models::couriers_model::UpdateCourierRating;
+use crate::models::ratings_model::{
+    CourierRatingInfo, CourierRatingsPage, CreateCourierRating, RatingsPageRequest,
+};
+use crate::repository::{assignments_repository, couriers_repository, ratings_repository};
+use crate::resources::postgres::DbConn;
+use crate::services::analytics_service::record_event;
+use crate::utils::configs::Config;
+use crate::utils::errors::AppError;
+use diesel::result::{DatabaseErrorKind, Error};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use uuid::Uuid;
+use validator::Validate;
+
+pub const MIN_SCORE: f64 = 0.0;
+pub const MAX_SCORE: f64 = 5.0;
+const RECENT_REVIEWS_LIMIT: i64 = 5;
+
+// Saving user's score for the delivered order and recomputing courier's rating.
+// Rating is a bayesian average, so a couple of scores can't move it too far from the prior
+pub async fn rate_courier(
+    config: &Config,
+    db_conn: &mut DbConn<'_>,
+    new_rating: CreateCourierRating,
+) -> Result<UpdateCourierRating, AppError> {
+    if !(MIN_SCORE..=MAX_SCORE).contains(&new_rating.score) {
+        return Err(AppError::validation_error(format!(
+            "Rating must be between {MIN_SCORE} and {MAX_SCORE}"
+        )));
+    }
+    let prior_mean = config.rating_prior_mean;
+    let prior_weight = config.rating_prior_weight;
+
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let assignment =
+                    assignments_repository::select_assignment(db_conn, new_rating.assignment_id)
+                        .await?
+                        .ok_or_else(|| AppError::validation_error("Unknown assignment"))?;
+                if assignment.courier_uuid != new_rating.courier_uuid
+                    || assignment.user_uuid != new_rating.rater_uuid
+                {
+                    return Err(AppError::validation_error(
+                        "Assignment doesn't belong to this courier and user",
+                    ));
+                }
+                if assignment.status != "DELIVERED" {
+                    return Err(AppError::validation_error(
+                        "Only delivered orders can be rated",
+                    ));
+                }
+
+                // Concurrent ratings of the same courier are serialized,
+                // otherwise one of them could be lost in the average
+                let courier = new_rating.courier_uuid;
+                couriers_repository::select_courier_for_update(db_conn, courier)
+                    .await?
+                    .ok_or_else(|| AppError::not_found_error("Courier not found"))?;
+
+                if ratings_repository::select_assignment_rating(db_conn, assignment.id)
+                    .await?
+                    .is_some()
+                {
+                    return Err(AppError::validation_error("Order is already rated"));
+                }
+                let rating = ratings_repository::create_rating(db_conn, new_rating)
+                    .await
+                    .map_err(|e| match e {
+                        Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                            AppError::validation_error("Order is already rated")
+                        }
+                        e => AppError::db_error(e),
+                    })?;
+                let event = AnalyticsEvent {
+                    courier_uuid: Some(courier),
+                    queue_id: assignment.queue_id,
+                    assignment_id: Some(assignment.id),
+                    rating: Some(rating.score),
+                    ..AnalyticsEvent::new("RATED", rating.rater_uuid, rating.created_at)
+                };
+                record_event(db_conn, event).await?;
+
+                let (ratings_count, scores_sum) =
+                    ratings_repository::select_courier_scores_summary(db_conn, courier).await?;
+                let new_info = UpdateCourierRating {
+                    rating: bayesian_average(
+                        prior_mean,
+                        prior_weight,
+                        scores_sum.unwrap_or_default(),
+                        ratings_count,
+                    ),
+                    ratings_count,
+                };
+                couriers_repository::update_courier_rating(db_conn, courier, new_info.clone())
+                    .await?;
+                Ok(new_info)
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+fn bayesian_average(prior_mean: f64, prior_weight: f64, scores_sum: f64, count: i64) -> f64 {
+    let weight = prior_weight + count as f64;
+    if weight <= 0.0 {
+        return prior_mean;
+    }
+    (prior_mean * prior_weight + scores_sum) / weight
+}
+
+pub async fn get_courier_rating(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<CourierRatingInfo, AppError> {
+    let summary = ratings_repository::select_courier_rating_summary(db_conn, courier)
+        .await?
+        .ok_or_else(|| AppError::not_found_error("Courier not found"))?;
+    let reviews =
+        ratings_repository::select_recent_reviews(db_conn, courier, RECENT_REVIEWS_LIMIT).await?;
+    Ok(CourierRatingInfo::new(summary, reviews))
+}
+
+// Public listing, couriers who haven't passed onboarding aren't shown
+pub async fn list_courier_ratings(
+    db_conn: &mut DbConn<'_>,
+    request: RatingsPageRequest,
+) -> Result<CourierRatingsPage, AppError> {
+    request
+        .validate()
+        .map_err(|e| AppError::validation_error(e.to_string()))?;
+    let offset = (request.page - 1) * request.limit;
+    let summaries = ratings_repository::select_couriers_rating_summaries(
+        db_conn,
+        request.sort,
+        offset,
+        request.limit,
+    )
+    .await?;
+    let total = ratings_repository::count_approved_couriers(db_conn).await?;
+
+    let mut couriers = Vec::with_capacity(summaries.len());
+    for summary in summaries {
+        let reviews = ratings_repository::select_recent_reviews(
+            db_conn,
+            summary.courier_uuid,
+            RECENT_REVIEWS_LIMIT,
+        )
+        .await?;
+        couriers.push(CourierRatingInfo::new(summary, reviews));
+    }
+    Ok(CourierRatingsPage {
+        couriers,
+        total,
+        page: request.page,
+        limit: request.limit,
+    })
+}
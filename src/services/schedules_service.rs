@@ -0,0 +1,245 @@
+use crate::models::schedules_model::{
+    AvailabilityWindow, AvailabilityWindowForm, CapacityForecastQuery, CreateAvailabilityWindow,
+    CreatePlannedShift, HourForecast, PlannedShift, PlannedShiftForm, PlannedShiftsQuery,
+};
+use crate::repository::{couriers_repository, queue_repository, schedules_repository};
+use crate::resources::postgres::DbConn;
+use crate::utils::configs::Config;
+use crate::utils::errors::AppError;
+use chrono::{Datelike, Duration, DurationRound, NaiveDateTime, Timelike, Utc};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+const MAX_PLANNED_SHIFT_HOURS: i64 = 16;
+const DEFAULT_PLANNED_SHIFTS_PERIOD_DAYS: i64 = 7;
+const DEFAULT_FORECAST_HOURS: i64 = 24;
+// Demand is averaged over the same hours of this many previous weeks
+const FORECAST_HISTORY_WEEKS: i64 = 4;
+
+pub async fn get_availability(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<Vec<AvailabilityWindow>, AppError> {
+    schedules_repository::select_courier_availability(db_conn, courier)
+        .await
+        .map_err(AppError::db_error)
+}
+
+// Whole weekly schedule is replaced, windows of one day must not overlap
+pub async fn set_availability(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    mut windows: Vec<AvailabilityWindowForm>,
+) -> Result<Vec<AvailabilityWindow>, AppError> {
+    windows.sort_by_key(|window| (window.day_of_week, window.start_time));
+    if windows
+        .iter()
+        .any(|window| window.start_time >= window.end_time)
+    {
+        return Err(AppError::validation_error(
+            "Window must start earlier than it ends",
+        ));
+    }
+    if windows.windows(2).any(|pair| {
+        pair[0].day_of_week == pair[1].day_of_week && pair[0].end_time > pair[1].start_time
+    }) {
+        return Err(AppError::validation_error(
+            "Windows of the same day must not overlap",
+        ));
+    }
+
+    let new_windows = windows
+        .into_iter()
+        .map(|window| CreateAvailabilityWindow {
+            courier_uuid: courier,
+            day_of_week: window.day_of_week,
+            start_time: window.start_time,
+            end_time: window.end_time,
+        })
+        .collect();
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                schedules_repository::replace_courier_availability(db_conn, courier, new_windows)
+                    .await?;
+                Ok(schedules_repository::select_courier_availability(db_conn, courier).await?)
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+// Planned shifts of one courier must not overlap
+pub async fn create_planned_shift(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    admin: Uuid,
+    form: PlannedShiftForm,
+) -> Result<PlannedShift, AppError> {
+    if form.starts_at >= form.ends_at {
+        return Err(AppError::validation_error(
+            "Shift must start earlier than it ends",
+        ));
+    }
+    if form.ends_at - form.starts_at > Duration::hours(MAX_PLANNED_SHIFT_HOURS) {
+        return Err(AppError::validation_error(
+            "Shift can't be longer than 16 hours",
+        ));
+    }
+    if form.ends_at <= Utc::now().naive_utc() {
+        return Err(AppError::validation_error(
+            "Shift can't be planned in the past",
+        ));
+    }
+
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                couriers_repository::select_courier_for_update(db_conn, courier)
+                    .await?
+                    .ok_or_else(|| AppError::not_found_error("Courier not found"))?;
+                let overlapping = schedules_repository::select_planned_shifts_between(
+                    db_conn,
+                    Some(courier),
+                    form.starts_at,
+                    form.ends_at,
+                )
+                .await?;
+                if !overlapping.is_empty() {
+                    return Err(AppError::conflict_error(
+                        "Shift overlaps with another planned shift of the courier",
+                    ));
+                }
+                let shift = CreatePlannedShift {
+                    courier_uuid: courier,
+                    starts_at: form.starts_at,
+                    ends_at: form.ends_at,
+                    created_by: admin,
+                };
+                Ok(schedules_repository::create_planned_shift(db_conn, shift).await?)
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+pub async fn delete_planned_shift(db_conn: &mut DbConn<'_>, shift_id: i64) -> Result<(), AppError> {
+    let deleted = schedules_repository::delete_planned_shift(db_conn, shift_id).await?;
+    if deleted == 0 {
+        return Err(AppError::not_found_error("Planned shift not found"));
+    }
+    Ok(())
+}
+
+pub async fn get_planned_shifts(
+    db_conn: &mut DbConn<'_>,
+    courier: Option<Uuid>,
+    period: PlannedShiftsQuery,
+) -> Result<Vec<PlannedShift>, AppError> {
+    let from = period.from.unwrap_or_else(|| Utc::now().naive_utc());
+    let to = period
+        .to
+        .unwrap_or(from + Duration::days(DEFAULT_PLANNED_SHIFTS_PERIOD_DAYS));
+    if from >= to {
+        return Err(AppError::validation_error(
+            "Beginning of the period must be earlier than its end",
+        ));
+    }
+    schedules_repository::select_planned_shifts_between(db_conn, courier, from, to)
+        .await
+        .map_err(AppError::db_error)
+}
+
+// Planned courier hours against demand expected from the queue history
+// of the same weekday and hour
+pub async fn get_capacity_forecast(
+    config: &Config,
+    db_conn: &mut DbConn<'_>,
+    query: CapacityForecastQuery,
+) -> Result<Vec<HourForecast>, AppError> {
+    let from = query
+        .from
+        .unwrap_or_else(|| Utc::now().naive_utc())
+        .duration_trunc(Duration::hours(1))
+        .map_err(|_| AppError::validation_error("Invalid beginning of the forecast"))?;
+    let hours = query.hours.unwrap_or(DEFAULT_FORECAST_HOURS);
+    let to = from + Duration::hours(hours);
+
+    let history_from = from - Duration::weeks(FORECAST_HISTORY_WEEKS);
+    let mut demand: HashMap<(u32, u32), i64> = HashMap::new();
+    for created_at in queue_repository::select_queue_created_between(db_conn, history_from, from)
+        .await
+        .map_err(AppError::db_error)?
+    {
+        *demand.entry(weekday_hour(created_at)).or_default() += 1;
+    }
+
+    let planned_shifts =
+        schedules_repository::select_planned_shifts_between(db_conn, None, from, to)
+            .await
+            .map_err(AppError::db_error)?;
+    let availability = schedules_repository::select_all_availability(db_conn)
+        .await
+        .map_err(AppError::db_error)?;
+
+    let forecast = (0..hours)
+        .map(|hour| {
+            let hour_start = from + Duration::hours(hour);
+            let hour_end = hour_start + Duration::hours(1);
+
+            let expected_orders = demand
+                .get(&weekday_hour(hour_start))
+                .copied()
+                .unwrap_or_default() as f64
+                / FORECAST_HISTORY_WEEKS as f64;
+            let required_courier_hours = expected_orders / config.orders_per_courier_hour;
+
+            let planned_seconds: i64 = planned_shifts
+                .iter()
+                .map(|shift| {
+                    let overlap = shift.ends_at.min(hour_end) - shift.starts_at.max(hour_start);
+                    overlap.num_seconds().max(0)
+                })
+                .sum();
+            let planned_courier_hours = planned_seconds as f64 / 3600.0;
+
+            let day_of_week = hour_start.weekday().number_from_monday() as i16;
+            let hour_start_seconds = hour_start.hour() * 3600;
+            let available_couriers = availability
+                .iter()
+                .filter(|window| {
+                    window.day_of_week == day_of_week
+                        && window.start_time.num_seconds_from_midnight() <= hour_start_seconds
+                        && window_end_seconds(window) >= hour_start_seconds + 3600
+                })
+                .map(|window| window.courier_uuid)
+                .collect::<HashSet<Uuid>>()
+                .len() as i64;
+
+            HourForecast {
+                hour_start,
+                expected_orders,
+                required_courier_hours,
+                planned_courier_hours,
+                available_couriers,
+                shortage_courier_hours: (required_courier_hours - planned_courier_hours).max(0.0),
+            }
+        })
+        .collect();
+    Ok(forecast)
+}
+
+fn weekday_hour(moment: NaiveDateTime) -> (u32, u32) {
+    (moment.weekday().num_days_from_monday(), moment.hour())
+}
+
+// 23:59:59 is the latest time that can be stored, it stands for midnight
+fn window_end_seconds(window: &AvailabilityWindow) -> u32 {
+    let end_seconds = window.end_time.num_seconds_from_midnight();
+    if end_seconds >= 24 * 3600 - 1 {
+        24 * 3600
+    } else {
+        end_seconds
+    }
+}
@@ -0,0 +1,133 @@
+use crate::models::couriers_model::UpdateCourier;
+use crate::models::shifts_model::{CreateShift, ShiftInfo, UpdateShift};
+use crate::repository::{couriers_repository, shifts_repository};
+use crate::resources::postgres::DbConn;
+use crate::utils::errors::AppError;
+use chrono::Utc;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use uuid::Uuid;
+
+const SHIFTS_HISTORY_LIMIT: i64 = 30;
+
+// Starting new shift or resuming paused one
+pub async fn start_shift(db_conn: &mut DbConn<'_>, courier: Uuid) -> Result<ShiftInfo, AppError> {
+    let shift = db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let open_shift = shifts_repository::select_open_shift(db_conn, courier).await?;
+                let shift = match open_shift {
+                    Some(shift) if shift.status == "ACTIVE" => {
+                        return Err(AppError::conflict_error("Shift is already started"));
+                    }
+                    Some(shift) => {
+                        let now = Utc::now().naive_utc();
+                        let paused_for = (now - shift.paused_at.unwrap_or(now)).num_seconds();
+                        let new_info = UpdateShift {
+                            status: Some("ACTIVE".to_string()),
+                            paused_at: Some(None),
+                            paused_seconds: Some(shift.paused_seconds + paused_for),
+                            finished_at: None,
+                        };
+                        shifts_repository::update_shift(db_conn, shift.id, new_info).await?
+                    }
+                    None => {
+                        let new_shift = CreateShift {
+                            courier_uuid: courier,
+                        };
+                        shifts_repository::create_shift(db_conn, new_shift).await?
+                    }
+                };
+                set_courier_on_shift(db_conn, courier, true).await?;
+                // Courier who has just started shift must not be taken offline by sweeper
+                couriers_repository::touch_courier(db_conn, courier, Utc::now().naive_utc())
+                    .await?;
+                Ok(shift)
+            }
+            .scope_boxed()
+        })
+        .await?;
+    Ok(ShiftInfo::new(shift, Utc::now().naive_utc()))
+}
+
+pub async fn pause_shift(db_conn: &mut DbConn<'_>, courier: Uuid) -> Result<ShiftInfo, AppError> {
+    let shift = db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let shift = shifts_repository::select_open_shift(db_conn, courier)
+                    .await?
+                    .ok_or_else(|| AppError::not_found_error("There is no started shift"))?;
+                if shift.status == "PAUSED" {
+                    return Err(AppError::conflict_error("Shift is already paused"));
+                }
+                let new_info = UpdateShift {
+                    status: Some("PAUSED".to_string()),
+                    paused_at: Some(Some(Utc::now().naive_utc())),
+                    paused_seconds: None,
+                    finished_at: None,
+                };
+                let shift = shifts_repository::update_shift(db_conn, shift.id, new_info).await?;
+                set_courier_on_shift(db_conn, courier, false).await?;
+                Ok(shift)
+            }
+            .scope_boxed()
+        })
+        .await?;
+    Ok(ShiftInfo::new(shift, Utc::now().naive_utc()))
+}
+
+pub async fn end_shift(db_conn: &mut DbConn<'_>, courier: Uuid) -> Result<ShiftInfo, AppError> {
+    let shift = db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let shift = shifts_repository::select_open_shift(db_conn, courier)
+                    .await?
+                    .ok_or_else(|| AppError::not_found_error("There is no started shift"))?;
+                let now = Utc::now().naive_utc();
+                // Closing current pause before finishing the shift
+                let paused_for = match shift.paused_at {
+                    Some(paused_at) => (now - paused_at).num_seconds(),
+                    None => 0,
+                };
+                let new_info = UpdateShift {
+                    status: Some("FINISHED".to_string()),
+                    paused_at: Some(None),
+                    paused_seconds: Some(shift.paused_seconds + paused_for),
+                    finished_at: Some(Some(now)),
+                };
+                let shift = shifts_repository::update_shift(db_conn, shift.id, new_info).await?;
+                set_courier_on_shift(db_conn, courier, false).await?;
+                Ok(shift)
+            }
+            .scope_boxed()
+        })
+        .await?;
+    Ok(ShiftInfo::new(shift, Utc::now().naive_utc()))
+}
+
+pub async fn get_shifts_history(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<Vec<ShiftInfo>, AppError> {
+    let now = Utc::now().naive_utc();
+    let shifts = shifts_repository::select_courier_shifts(db_conn, courier, SHIFTS_HISTORY_LIMIT)
+        .await
+        .map_err(AppError::db_error)?;
+    Ok(shifts
+        .into_iter()
+        .map(|shift| ShiftInfo::new(shift, now))
+        .collect())
+}
+
+pub async fn set_courier_on_shift(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    on_shift: bool,
+) -> Result<(), AppError> {
+    let new_info = UpdateCourier {
+        is_free: None,
+        rating: None,
+        is_on_shift: Some(on_shift),
+    };
+    couriers_repository::update_courier(db_conn, courier, new_info).await?;
+    Ok(())
+}
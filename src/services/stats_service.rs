@@ -0,0 +1,95 @@
+use crate::models::stats_model::{CourierStats, StatsPeriodQuery};
+use crate::repository::{
+    assignments_repository, offers_repository, ratings_repository, shifts_repository,
+};
+use crate::resources::postgres::DbConn;
+use crate::utils::configs::Config;
+use crate::utils::errors::AppError;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+const DEFAULT_STATS_PERIOD_DAYS: i64 = 30;
+
+pub async fn get_courier_stats(
+    config: &Config,
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    period: StatsPeriodQuery,
+) -> Result<CourierStats, AppError> {
+    let now = Utc::now().naive_utc();
+    let to = period.to.unwrap_or(now);
+    let from = period
+        .from
+        .unwrap_or(to - Duration::days(DEFAULT_STATS_PERIOD_DAYS));
+    if from >= to {
+        return Err(AppError::validation_error(
+            "Beginning of the period must be earlier than its end",
+        ));
+    }
+
+    let summary = ratings_repository::select_courier_rating_summary(db_conn, courier)
+        .await
+        .map_err(AppError::db_error)?
+        .ok_or_else(|| AppError::not_found_error("Courier not found"))?;
+
+    let delivered = assignments_repository::select_delivered_between(db_conn, courier, from, to)
+        .await
+        .map_err(AppError::db_error)?;
+    let deliveries_completed = delivered.len() as i64;
+    let delivery_durations: Vec<i64> = delivered
+        .into_iter()
+        .filter_map(|(assigned_at, delivered_at)| Some((delivered_at? - assigned_at).num_seconds()))
+        .collect();
+    let average_delivery_seconds = (!delivery_durations.is_empty())
+        .then(|| delivery_durations.iter().sum::<i64>() / delivery_durations.len() as i64);
+    let deliveries_cancelled =
+        assignments_repository::count_cancelled_between(db_conn, courier, from, to)
+            .await
+            .map_err(AppError::db_error)?;
+
+    // Same as overall acceptance rate, couriers without answered offers have rate 1.0
+    let answered_offers =
+        offers_repository::count_courier_answered_offers_between(db_conn, courier, from, to)
+            .await
+            .map_err(AppError::db_error)?;
+    let answered: i64 = answered_offers.iter().map(|(_, count)| count).sum();
+    let accepted: i64 = answered_offers
+        .iter()
+        .filter(|(status, _)| status == "ACCEPTED")
+        .map(|(_, count)| count)
+        .sum();
+    let acceptance_rate = if answered == 0 {
+        1.0
+    } else {
+        accepted as f64 / answered as f64
+    };
+
+    let shifts = shifts_repository::select_shifts_between(db_conn, courier, from, to)
+        .await
+        .map_err(AppError::db_error)?;
+    let online_seconds: i64 = shifts
+        .iter()
+        .map(|shift| shift.worked_seconds_between(from, to, now))
+        .sum();
+
+    let period_average_score =
+        ratings_repository::select_average_score_between(db_conn, courier, from, to)
+            .await
+            .map_err(AppError::db_error)?;
+
+    Ok(CourierStats {
+        courier_uuid: courier,
+        from,
+        to,
+        deliveries_completed,
+        deliveries_cancelled,
+        acceptance_rate,
+        average_delivery_seconds,
+        online_hours: online_seconds as f64 / 3600.0,
+        delivery_fee: config.delivery_fee,
+        earnings: deliveries_completed as f64 * config.delivery_fee,
+        rating: summary.rating,
+        ratings_count: summary.ratings_count,
+        period_average_score,
+    })
+}
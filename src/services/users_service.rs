@@ -1,13 +1,22 @@
 use crate::middleware::jwt_middleware::get_token_claims;
-use crate::models::couriers_model::UpdateCourier;
-use crate::models::queue_model::AddUserToQueue;
-use crate::repository::couriers_repository::{find_free_courier, update_courier};
-use crate::repository::queue_repository;
+use crate::models::assignments_model::Assignment;
+use crate::models::locations_model::LocationPing;
+use crate::models::outbox_model::Notification;
+use crate::models::queue_model::{
+    AddUserToQueue, CourierSearchStatus, UserQueueInfo, MAX_QUEUE_PRIORITY,
+};
+use crate::models::ratings_model::{CourierRatingInfo, CreateCourierRating, RatingsPageRequest};
+use crate::repository::{assignments_repository, offers_repository, queue_repository};
 use crate::resources::postgres::DbPool;
 use crate::services::auth_service::hash_password;
+use crate::services::outbox_service::enqueue_notification;
+use crate::services::{
+    assignments_service, locations_service, offers_service, presence_service, queue_service,
+    ratings_service, zones_service,
+};
 use crate::utils::configs::Config;
 use crate::utils::errors::AppError;
-use crate::utils::grpc::analytics_grpc::analytics_client::AnalyticsClient;
+use crate::utils::geo::is_valid_coordinates;
 use crate::utils::grpc::users_grpc::users_server::Users;
 use crate::utils::grpc::{analytics_grpc::*, users_grpc::*};
 use crate::{
@@ -15,10 +24,27 @@ use crate::{
     repository::{couriers_repository, users_repository},
     resources::postgres::DbConn,
 };
-use chrono::{NaiveDateTime, Utc};
-use tonic::{Code, Request, Response, Status};
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::{Code, Request, Response, Status, Streaming};
+use tracing::error;
 use uuid::Uuid;
 
+const OFFERS_CHANNEL_CAPACITY: usize = 16;
+const OFFERS_POLLING_INTERVAL: Duration = Duration::from_secs(1);
+const SEARCH_CHANNEL_CAPACITY: usize = 16;
+const SEARCH_POLLING_INTERVAL: Duration = Duration::from_secs(1);
+// Waiting time changes every second, it is resent only this often if nothing else changed
+const ETA_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+// Streamed pings are saved in batches, same as the ones sent over HTTP
+const LOCATION_BATCH_SIZE: usize = 500;
+const LOCATION_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
 pub async fn create_user(
     new_user: &mut CreateUser,
     db_conn: &mut DbConn<'_>,
@@ -31,56 +57,69 @@ pub async fn create_user(
     )
     .await;
 
-    let user = users_repository::create_user(db_conn, new_user.clone())
+    // Courier is created together with his user. Analytics is told about
+    // registration through outbox, so sign-up doesn't depend on it
+    let new_user = new_user.clone();
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let user = users_repository::create_user(db_conn, new_user).await?;
+                let registration = if user.role == *"COURIER" {
+                    let new_courier = CreateCourier {
+                        user_uuid: user.uuid,
+                    };
+                    let courier = couriers_repository::create_courier(new_courier, db_conn).await?;
+                    Notification::Registered {
+                        uuid: user.uuid,
+                        role: "COURIER".to_string(),
+                        created_at: courier.created_at,
+                    }
+                } else {
+                    Notification::Registered {
+                        uuid: user.uuid,
+                        role: "USER".to_string(),
+                        created_at: user.created_at,
+                    }
+                };
+                enqueue_notification(db_conn, &registration).await
+            }
+            .scope_boxed()
+        })
         .await
-        .map_err(AppError::db_error)?;
-    if user.role == *"COURIER" {
-        let new_courier = CreateCourier {
-            user_uuid: user.uuid,
-        };
-        let courier = couriers_repository::create_courier(new_courier, db_conn)
-            .await
-            .map_err(AppError::db_error)?;
-
-        send_reg_info_to_analytics_service(config, user.uuid, "COURIER", courier.created_at)
-            .await?;
-        return Ok(());
-    }
-
-    send_reg_info_to_analytics_service(config, user.uuid, "USER", user.created_at).await?;
-    Ok(())
 }
 
-async fn send_reg_info_to_analytics_service(
+pub async fn send_reg_info_to_analytics_service(
     config: &Config,
     uuid: Uuid,
     role: &str,
     created_at: NaiveDateTime,
-) -> Result<(), AppError> {
-    let mut client = AnalyticsClient::connect(config.grpc_analytics_address.clone())
-        .await
-        .map_err(AppError::grpc_error)?;
-    let request = tonic::Request::new(SaveRegRequest {
+) -> Result<Response<SaveRegResponse>, Status> {
+    let request = SaveRegRequest {
         uuid: uuid.to_string(),
         role: role.to_owned(),
         created_at: created_at.to_string(),
-    });
-    client
-        .save_reg_info(request)
+    };
+    config
+        .analytics_grpc
+        .call(false, |mut client| {
+            let request = request.clone();
+            async move { client.save_reg_info(request).await }
+        })
         .await
-        // .map_err(|e| AppError::grpc_error(e))?;
-        .map_err(AppError::grpc_error)?;
-    Ok(())
 }
 
 pub struct UserService {
     pub db_pool: DbPool,
     pub create_order_crone: i32,
     pub jwt_secret: String,
+    pub config: Config,
 }
 
 #[tonic::async_trait]
 impl Users for UserService {
+    type SubscribeCourierOffersStream = ReceiverStream<Result<CourierOfferMessage, Status>>;
+    type WatchCourierSearchStream = ReceiverStream<Result<WaitForCourierResponse, Status>>;
+
     async fn send_token_claims(
         &self,
         request: Request<TokenClaimsRequest>,
@@ -110,52 +149,39 @@ impl Users for UserService {
         match queue {
             Err(e) => Err(Status::new(Code::Internal, e.to_string())),
 
-            // Find free courier and update his status if there is no queue
+            // Adding user to queue and offering the order to free courier right away
+            // if there is no queue. Courier is sent to order service after accepting the offer
             Ok(queue) if queue.is_empty() => {
                 println!("> empty queue");
+                let user = new_queue_position(&mut db_conn, request.into_inner()).await?;
+                println!("> adding user to queue");
+                let queue_position = queue_service::add_queue_position(&mut db_conn, user).await?;
                 println!("searching for courier");
-                let courier = find_free_courier(&mut db_conn)
-                    .await
-                    .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
-                println!("handling courier");
-                match courier {
-                    // Sending courier and updating his status in case there is free courier
-                    Some(courier) => {
-                        let new_info = UpdateCourier {
-                            is_free: Some(false),
-                            rating: None,
-                        };
-                        update_courier(&mut db_conn, courier.user_uuid, new_info)
-                            .await
-                            .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
-                        let response = FindCourierResponse {
-                            courier_uuid: courier.user_uuid.to_string(),
-                            added_to_queue: false,
-                            time_untill_next_try: 0,
-                        };
-                        Ok(Response::new(response))
-                    }
-                    // Adding user to queue in case there is no free couriers
-                    None => {
-                        println!("> none");
-                        let request = request.into_inner();
-                        let user = AddUserToQueue {
-                            user_uuid: Uuid::parse_str(&request.user_uuid)
-                                .expect("Cannot parse UUID"),
-                        };
-                        println!("> adding user to queue");
-                        queue_repository::add_user_to_queue(&mut db_conn, user)
-                            .await
-                            .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
-                        println!("> making response");
-                        let response = FindCourierResponse {
-                            courier_uuid: "None".to_string(),
-                            added_to_queue: true,
-                            time_untill_next_try: 0,
-                        };
-                        Ok(Response::new(response))
+                let courier = self
+                    .config
+                    .matching_service
+                    .find_courier(&mut db_conn, &queue_position, &[])
+                    .await?;
+                // User is already in queue, if courier or position has been taken
+                // by distributor meanwhile it will be offered by distributor
+                if let Some(courier) = courier {
+                    let offered = offers_service::create_offer(
+                        &mut db_conn,
+                        &queue_position,
+                        courier.user_uuid,
+                        self.config.courier_offer_timeout,
+                    )
+                    .await;
+                    if let Err(e) = offered {
+                        error!("Error offering order to courier, {e}");
                     }
                 }
+                println!("> making response");
+                let response = FindCourierResponse {
+                    added_to_queue: true,
+                    time_untill_next_try: 0,
+                };
+                Ok(Response::new(response))
             }
 
             // In case queue isn't empty
@@ -163,15 +189,12 @@ impl Users for UserService {
             // Adding user in queue
             Ok(queue) => {
                 println!("not empty queue");
-                let request = request.into_inner();
-
-                let uuid = Uuid::parse_str(&request.user_uuid).expect("Cannot parse UUID");
-                let user = AddUserToQueue { user_uuid: uuid };
+                let user = new_queue_position(&mut db_conn, request.into_inner()).await?;
+                let uuid = user.user_uuid;
                 // check if user already in queue
                 for user in queue {
                     if user.user_uuid == uuid {
                         let response = FindCourierResponse {
-                            courier_uuid: "None".to_string(),
                             added_to_queue: true,
                             time_untill_next_try: 0,
                         };
@@ -185,19 +208,15 @@ impl Users for UserService {
                         .await;
                 if time > 0 {
                     let response = FindCourierResponse {
-                        courier_uuid: "None".to_string(),
                         added_to_queue: false,
                         time_untill_next_try: time,
                     };
                     return Ok(Response::new(response));
                 }
                 println!("adding user to queue");
-                queue_repository::add_user_to_queue(&mut db_conn, user)
-                    .await
-                    .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
+                queue_service::add_queue_position(&mut db_conn, user).await?;
                 println!("making response");
                 let response = FindCourierResponse {
-                    courier_uuid: "None".to_string(),
                     added_to_queue: true,
                     time_untill_next_try: 0,
                 };
@@ -217,22 +236,66 @@ impl Users for UserService {
             .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
 
         let request = request.into_inner();
-        let rating = &request.rating;
-        let uuid = Uuid::parse_str(&request.courier_uuid).expect("Cannot parse Uuid");
-
-        let new_info = UpdateCourier {
-            is_free: None,
-            rating: Some(*rating as f64),
+        let new_rating = CreateCourierRating {
+            assignment_id: request.assignment_id,
+            courier_uuid: parse_uuid(&request.courier_uuid)?,
+            rater_uuid: parse_uuid(&request.rater_uuid)?,
+            score: request.rating as f64,
+            comment: request.comment.filter(|comment| !comment.trim().is_empty()),
         };
-        update_courier(&mut db_conn, uuid, new_info)
-            .await
-            .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
+        let new_info =
+            ratings_service::rate_courier(&self.config, &mut db_conn, new_rating).await?;
         let response = UpdateCourierRatingResponse {
             message: "Updated".to_string(),
+            courier_rating: new_info.rating,
+            ratings_count: new_info.ratings_count,
         };
         Ok(Response::new(response))
     }
 
+    async fn list_courier_ratings(
+        &self,
+        request: Request<ListCourierRatingsRequest>,
+    ) -> Result<Response<ListCourierRatingsResponse>, Status> {
+        let mut db_conn = self
+            .db_pool
+            .get()
+            .await
+            .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
+
+        let request = request.into_inner();
+        let page_request = RatingsPageRequest {
+            page: if request.page > 0 { request.page } else { 1 },
+            limit: if request.limit > 0 { request.limit } else { 20 },
+            sort: request.sort.parse().map_err(AppError::validation_error)?,
+        };
+        let page = ratings_service::list_courier_ratings(&mut db_conn, page_request).await?;
+        let response = ListCourierRatingsResponse {
+            couriers: page
+                .couriers
+                .into_iter()
+                .map(courier_rating_object)
+                .collect(),
+            total: page.total,
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn get_courier_rating(
+        &self,
+        request: Request<GetCourierRatingRequest>,
+    ) -> Result<Response<CourierRatingObject>, Status> {
+        let mut db_conn = self
+            .db_pool
+            .get()
+            .await
+            .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
+
+        let courier = parse_uuid(&request.into_inner().courier_uuid)?;
+        let rating = ratings_service::get_courier_rating(&mut db_conn, courier).await?;
+        Ok(Response::new(courier_rating_object(rating)))
+    }
+
     async fn wait_for_courier(
         &self,
         request: Request<WaitForCourierRequest>,
@@ -243,35 +306,474 @@ impl Users for UserService {
             .await
             .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
 
+        let uuid = parse_uuid(&request.into_inner().user_uuid)?;
+        let search = get_search_status(&self.config, &mut db_conn, uuid).await?;
+        Ok(Response::new(search_status_response(search)))
+    }
+
+    // Pushing search status on every change untill it is final
+    async fn watch_courier_search(
+        &self,
+        request: Request<WaitForCourierRequest>,
+    ) -> Result<Response<Self::WatchCourierSearchStream>, Status> {
+        let uuid = parse_uuid(&request.into_inner().user_uuid)?;
+        let mut updates = subscribe_search_status(self.config.clone(), self.db_pool.clone(), uuid);
+        let (sender, receiver) = mpsc::channel(SEARCH_CHANNEL_CAPACITY);
+        tokio::spawn(async move {
+            while let Some(update) = updates.next().await {
+                let message = match update {
+                    Ok(search) => Ok(search_status_response(search)),
+                    Err(e) => Err(Status::from(e)),
+                };
+                if sender.send(message).await.is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    async fn cancel_courier_search(
+        &self,
+        request: Request<CancelCourierSearchRequest>,
+    ) -> Result<Response<CancelCourierSearchResponse>, Status> {
+        let mut db_conn = self
+            .db_pool
+            .get()
+            .await
+            .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
+
+        let user = parse_uuid(&request.into_inner().user_uuid)?;
+        let position = queue_service::cancel_courier_search(&mut db_conn, user).await?;
+        let response = CancelCourierSearchResponse {
+            queue_id: position.id,
+            status: position.status,
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn get_assignment(
+        &self,
+        request: Request<AssignmentRequest>,
+    ) -> Result<Response<AssignmentResponse>, Status> {
+        let mut db_conn = self
+            .db_pool
+            .get()
+            .await
+            .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
+
+        let assignment_id = request.into_inner().assignment_id;
+        let assignment = assignments_service::get_assignment(&mut db_conn, assignment_id).await?;
+        Ok(Response::new(assignment_response(assignment)))
+    }
+
+    async fn pick_up_delivery(
+        &self,
+        request: Request<AssignmentRequest>,
+    ) -> Result<Response<AssignmentResponse>, Status> {
+        let mut db_conn = self
+            .db_pool
+            .get()
+            .await
+            .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
+
+        let assignment_id = request.into_inner().assignment_id;
+        let assignment =
+            assignments_service::pick_up_assignment(&mut db_conn, assignment_id, None).await?;
+        Ok(Response::new(assignment_response(assignment)))
+    }
+
+    async fn complete_delivery(
+        &self,
+        request: Request<AssignmentRequest>,
+    ) -> Result<Response<AssignmentResponse>, Status> {
+        let mut db_conn = self
+            .db_pool
+            .get()
+            .await
+            .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
+
+        let assignment_id = request.into_inner().assignment_id;
+        let assignment =
+            assignments_service::deliver_assignment(&mut db_conn, assignment_id, None).await?;
+        Ok(Response::new(assignment_response(assignment)))
+    }
+
+    async fn cancel_delivery(
+        &self,
+        request: Request<AssignmentRequest>,
+    ) -> Result<Response<AssignmentResponse>, Status> {
+        let mut db_conn = self
+            .db_pool
+            .get()
+            .await
+            .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
+
+        let assignment_id = request.into_inner().assignment_id;
+        let assignment =
+            assignments_service::cancel_assignment(&mut db_conn, assignment_id, None).await?;
+        Ok(Response::new(assignment_response(assignment)))
+    }
+
+    // Pushing new offers to the courier untill he disconnects
+    async fn subscribe_courier_offers(
+        &self,
+        request: Request<CourierOffersRequest>,
+    ) -> Result<Response<Self::SubscribeCourierOffersStream>, Status> {
+        let courier = parse_uuid(&request.into_inner().courier_uuid)?;
+        let (sender, receiver) = mpsc::channel(OFFERS_CHANNEL_CAPACITY);
+        tokio::spawn(watch_courier_offers(self.db_pool.clone(), courier, sender));
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    async fn respond_to_courier_offer(
+        &self,
+        request: Request<CourierOfferAnswerRequest>,
+    ) -> Result<Response<CourierOfferAnswerResponse>, Status> {
+        let mut db_conn = self
+            .db_pool
+            .get()
+            .await
+            .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
+
         let request = request.into_inner();
-        let uuid = Uuid::parse_str(&request.user_uuid).expect("Cannot parse Uuid");
+        let courier = parse_uuid(&request.courier_uuid)?;
+        let response = if request.accepted {
+            let assignment =
+                offers_service::accept_offer(&mut db_conn, request.offer_id, courier).await?;
+            CourierOfferAnswerResponse {
+                status: "ACCEPTED".to_string(),
+                assignment_id: assignment.id,
+            }
+        } else {
+            let offer =
+                offers_service::decline_offer(&mut db_conn, request.offer_id, courier).await?;
+            CourierOfferAnswerResponse {
+                status: offer.status,
+                assignment_id: 0,
+            }
+        };
+        Ok(Response::new(response))
+    }
 
-        let queue = queue_repository::select_queue_info(&mut db_conn, uuid)
+    async fn report_courier_location(
+        &self,
+        request: Request<Streaming<CourierLocationPing>>,
+    ) -> Result<Response<ReportCourierLocationResponse>, Status> {
+        let mut db_conn = self
+            .db_pool
+            .get()
             .await
             .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
-        match queue.status.as_ref() {
-            "SEARCHING" => {
-                let time = count_average_waiting_time(&mut db_conn, queue.id).await;
-                let response = WaitForCourierResponse {
-                    status: queue.status,
-                    avg_waiting_time: time,
-                };
-                Ok(Response::new(response))
+
+        let mut pings = request.into_inner();
+        let mut buffered_pings: HashMap<Uuid, Vec<LocationPing>> = HashMap::new();
+        let mut buffered_count = 0;
+        let mut accepted_pings = 0;
+        let mut flush_interval = tokio::time::interval(LOCATION_FLUSH_INTERVAL);
+        loop {
+            tokio::select! {
+                ping = pings.next() => {
+                    let ping = match ping {
+                        Some(ping) => ping?,
+                        None => break,
+                    };
+                    let courier = parse_uuid(&ping.courier_uuid)?;
+                    if !is_valid_coordinates(ping.latitude, ping.longitude) {
+                        return Err(AppError::validation_error("Invalid coordinates").into());
+                    }
+                    let recorded_at = Utc
+                        .timestamp_opt(ping.recorded_at, 0)
+                        .single()
+                        .filter(|_| ping.recorded_at > 0)
+                        .map(|recorded_at| recorded_at.naive_utc());
+                    buffered_pings.entry(courier).or_default().push(LocationPing {
+                        latitude: ping.latitude,
+                        longitude: ping.longitude,
+                        recorded_at,
+                    });
+                    buffered_count += 1;
+                    if buffered_count < LOCATION_BATCH_SIZE {
+                        continue;
+                    }
+                }
+                _ = flush_interval.tick() => {}
             }
-            _ => {
-                let response = WaitForCourierResponse {
-                    status: queue.status,
-                    avg_waiting_time: 0,
-                };
-                Ok(Response::new(response))
+            accepted_pings += save_buffered_pings(&mut db_conn, &mut buffered_pings).await?;
+            buffered_count = 0;
+        }
+        accepted_pings += save_buffered_pings(&mut db_conn, &mut buffered_pings).await?;
+        let response = ReportCourierLocationResponse { accepted_pings };
+        Ok(Response::new(response))
+    }
+
+    async fn courier_heartbeat(
+        &self,
+        request: Request<CourierHeartbeatRequest>,
+    ) -> Result<Response<CourierHeartbeatResponse>, Status> {
+        let mut db_conn = self
+            .db_pool
+            .get()
+            .await
+            .map_err(|e| Status::new(Code::Internal, e.to_string()))?;
+
+        let courier = parse_uuid(&request.into_inner().courier_uuid)?;
+        let heartbeat = presence_service::heartbeat(&mut db_conn, courier).await?;
+        let response = CourierHeartbeatResponse {
+            is_on_shift: heartbeat.is_on_shift,
+        };
+        Ok(Response::new(response))
+    }
+}
+
+// Position is tagged with the zone of its pickup point
+async fn new_queue_position(
+    db_conn: &mut DbConn<'_>,
+    request: FindCourierRequest,
+) -> Result<AddUserToQueue, AppError> {
+    let user_uuid = parse_uuid(&request.user_uuid)?;
+    let (pickup_latitude, pickup_longitude) =
+        match (request.pickup_latitude, request.pickup_longitude) {
+            (Some(latitude), Some(longitude)) if is_valid_coordinates(latitude, longitude) => {
+                (Some(latitude), Some(longitude))
+            }
+            (None, None) => (None, None),
+            _ => return Err(AppError::validation_error("Invalid pickup point")),
+        };
+    let is_valid_size =
+        |size: Option<f64>| size.map_or(true, |size| size.is_finite() && size > 0.0);
+    if !is_valid_size(request.order_weight_kg) || !is_valid_size(request.order_volume_liters) {
+        return Err(AppError::validation_error("Order size must be positive"));
+    }
+    let priority = request.priority.unwrap_or_default();
+    if !(0..=MAX_QUEUE_PRIORITY).contains(&priority) {
+        return Err(AppError::validation_error("Priority must be from 0 to 10"));
+    }
+    let zone_id = match pickup_latitude.zip(pickup_longitude) {
+        Some(pickup_point) => zones_service::find_zone_for_point(db_conn, pickup_point).await?,
+        None => None,
+    };
+    Ok(AddUserToQueue {
+        user_uuid,
+        pickup_latitude,
+        pickup_longitude,
+        order_weight_kg: request.order_weight_kg,
+        order_volume_liters: request.order_volume_liters,
+        zone_id,
+        priority,
+    })
+}
+
+fn courier_rating_object(rating: CourierRatingInfo) -> CourierRatingObject {
+    CourierRatingObject {
+        courier_uuid: rating.courier_uuid.to_string(),
+        courier_rating: rating.rating,
+        ratings_count: rating.ratings_count,
+        recent_reviews: rating
+            .recent_reviews
+            .into_iter()
+            .map(|review| CourierReview {
+                assignment_id: review.assignment_id,
+                score: review.score,
+                comment: review.comment,
+                created_at: review.created_at.to_string(),
+            })
+            .collect(),
+    }
+}
+
+// Saving every courier's pings as one batch, returns the number of saved pings
+async fn save_buffered_pings(
+    db_conn: &mut DbConn<'_>,
+    buffered_pings: &mut HashMap<Uuid, Vec<LocationPing>>,
+) -> Result<i32, AppError> {
+    let mut saved_pings = 0;
+    for (courier, pings) in buffered_pings.drain() {
+        saved_pings += pings.len() as i32;
+        locations_service::save_location_pings(db_conn, courier, pings).await?;
+    }
+    Ok(saved_pings)
+}
+
+fn parse_uuid(uuid: &str) -> Result<Uuid, AppError> {
+    Uuid::parse_str(uuid).map_err(|e| AppError::validation_error(format!("Invalid uuid: {e}")))
+}
+
+// Polling pending offers of the courier and sending those which weren't sent yet.
+// Stops as soon as the client closes the stream
+async fn watch_courier_offers(
+    db_pool: DbPool,
+    courier: Uuid,
+    sender: mpsc::Sender<Result<CourierOfferMessage, Status>>,
+) {
+    let mut sent_offers = HashSet::new();
+    while !sender.is_closed() {
+        let offers = match db_pool.get().await {
+            Ok(mut db_conn) => {
+                offers_repository::select_pending_courier_offers(&mut db_conn, courier).await
+            }
+            Err(e) => {
+                error!("Error getting db connection for offers stream {e}");
+                Ok(Vec::new())
+            }
+        };
+        match offers {
+            Ok(offers) => {
+                for offer in offers {
+                    if !sent_offers.insert(offer.id) {
+                        continue;
+                    }
+                    let message = CourierOfferMessage {
+                        offer_id: offer.id,
+                        user_uuid: offer.user_uuid.to_string(),
+                        expires_at: offer.expires_at.to_string(),
+                    };
+                    if sender.send(Ok(message)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            Err(e) => {
+                let _ = sender
+                    .send(Err(Status::new(Code::Internal, e.to_string())))
+                    .await;
+                return;
             }
         }
+        tokio::time::sleep(OFFERS_POLLING_INTERVAL).await;
+    }
+}
+
+// Status of user's latest search, waiting time is forecasted only while searching
+pub async fn get_search_status(
+    config: &Config,
+    db_conn: &mut DbConn<'_>,
+    user: Uuid,
+) -> Result<CourierSearchStatus, AppError> {
+    let queue = match queue_repository::select_queue_info(db_conn, user).await {
+        Ok(queue) => queue,
+        Err(diesel::result::Error::NotFound) => {
+            return Err(AppError::not_found_error(
+                "User hasn't searched for courier yet",
+            ))
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let mut search = CourierSearchStatus {
+        queue_id: queue.id,
+        status: queue.status,
+        avg_waiting_time: 0,
+        queue_position: 0,
+        courier_uuid: None,
+    };
+    match search.status.as_ref() {
+        "SEARCHING" => {
+            let searching_queue = queue_service::select_ordered_queue(config, db_conn).await?;
+            let index = searching_queue
+                .iter()
+                .position(|position| position.id == queue.id)
+                .unwrap_or(searching_queue.len());
+            search.avg_waiting_time =
+                count_average_waiting_time(db_conn, queue.id, &searching_queue[..index]).await;
+            search.queue_position = index as i32 + 1;
+        }
+        "COMPLETED" => {
+            search.courier_uuid =
+                assignments_repository::select_queue_assignment(db_conn, queue.id)
+                    .await?
+                    .map(|assignment| assignment.courier_uuid);
+        }
+        _ => {}
+    }
+    Ok(search)
+}
+
+// Stream of search status changes, the last item is the final status
+pub fn subscribe_search_status(
+    config: Config,
+    db_pool: DbPool,
+    user: Uuid,
+) -> ReceiverStream<Result<CourierSearchStatus, AppError>> {
+    let (sender, receiver) = mpsc::channel(SEARCH_CHANNEL_CAPACITY);
+    tokio::spawn(watch_search_status(config, db_pool, user, sender));
+    ReceiverStream::new(receiver)
+}
+
+// Polling user's search and sending status whenever it or the position changes.
+// Stops after sending the final status or as soon as the client closes the stream
+async fn watch_search_status(
+    config: Config,
+    db_pool: DbPool,
+    user: Uuid,
+    sender: mpsc::Sender<Result<CourierSearchStatus, AppError>>,
+) {
+    let mut last_sent: Option<(CourierSearchStatus, Instant)> = None;
+    while !sender.is_closed() {
+        let search = match db_pool.get().await {
+            Ok(mut db_conn) => get_search_status(&config, &mut db_conn, user).await,
+            Err(e) => {
+                error!("Error getting db connection for search status stream {e}");
+                tokio::time::sleep(SEARCH_POLLING_INTERVAL).await;
+                continue;
+            }
+        };
+        let search = match search {
+            Ok(search) => search,
+            Err(e) => {
+                let _ = sender.send(Err(e)).await;
+                return;
+            }
+        };
+        let is_changed = match &last_sent {
+            None => true,
+            Some((sent, sent_at)) => {
+                sent.queue_id != search.queue_id
+                    || sent.status != search.status
+                    || sent.queue_position != search.queue_position
+                    || (sent.avg_waiting_time != search.avg_waiting_time
+                        && sent_at.elapsed() >= ETA_REFRESH_INTERVAL)
+            }
+        };
+        if is_changed {
+            let is_final = search.is_final();
+            if sender.send(Ok(search.clone())).await.is_err() || is_final {
+                return;
+            }
+            last_sent = Some((search, Instant::now()));
+        }
+        tokio::time::sleep(SEARCH_POLLING_INTERVAL).await;
+    }
+}
+
+fn search_status_response(search: CourierSearchStatus) -> WaitForCourierResponse {
+    WaitForCourierResponse {
+        status: search.status,
+        avg_waiting_time: search.avg_waiting_time,
+        queue_position: search.queue_position,
+        courier_uuid: search
+            .courier_uuid
+            .map(|courier| courier.to_string())
+            .unwrap_or_default(),
+    }
+}
+
+fn assignment_response(assignment: Assignment) -> AssignmentResponse {
+    AssignmentResponse {
+        assignment_id: assignment.id,
+        user_uuid: assignment.user_uuid.to_string(),
+        courier_uuid: assignment.courier_uuid.to_string(),
+        status: assignment.status,
     }
 }
 
 // Count average waiting time for current user
 // By default 180 sec just in case there are some problems with database
-async fn count_average_waiting_time(db_conn: &mut DbConn<'_>, queue_id: i64) -> i32 {
+// `queue_ahead` are positions which will be offered to couriers before the user's one
+async fn count_average_waiting_time(
+    db_conn: &mut DbConn<'_>,
+    queue_id: i64,
+    queue_ahead: &[UserQueueInfo],
+) -> i32 {
     let default_time = 180;
     let completed_positions =
         queue_repository::select_last_ten_completed_positions(db_conn, queue_id).await;
@@ -282,18 +784,13 @@ async fn count_average_waiting_time(db_conn: &mut DbConn<'_>, queue_id: i64) ->
         // Result based on waiting time of first person in queue
         // and position of current user
         Ok(completed_positions) if completed_positions.is_empty() => {
-            let current_queue =
-                queue_repository::select_queue_untill_position(db_conn, queue_id).await;
-
-            if let Ok(queue) = current_queue {
-                let first_position = queue.first();
-                if let Some(info) = first_position {
-                    let first_person_time = Utc::now().naive_utc() - info.created_at;
-                    let forecast_time =
-                        first_person_time.num_seconds() as i32 * (queue.len() as i32);
-                    println!("QUEUE LEN::::: {}", queue.len());
-                    return forecast_time;
-                }
+            let first_position = queue_ahead.first();
+            if let Some(info) = first_position {
+                let first_person_time = Utc::now().naive_utc() - info.created_at;
+                let forecast_time =
+                    first_person_time.num_seconds() as i32 * (queue_ahead.len() as i32);
+                println!("QUEUE LEN::::: {}", queue_ahead.len());
+                return forecast_time;
             }
             default_time
         }
@@ -315,19 +812,15 @@ async fn count_average_waiting_time(db_conn: &mut DbConn<'_>, queue_id: i64) ->
                 println!("AVERAGE TIME: {:?}", average_time);
             // Adding to forecast time of current persons in queue
             // if their waiting time already more than average time
-            if let Ok(queue) =
-                queue_repository::select_queue_untill_position(db_conn, queue_id).await
-            {
-                for position in queue {
-                    let pos_time = (current_time - position.created_at).num_seconds() as i32;
-
-                    if pos_time > average_time {
-                        time_for_each_position.push(pos_time)
-                    } else {
-                        break;
-                    }
+            for position in queue_ahead {
+                let pos_time = (current_time - position.created_at).num_seconds() as i32;
+
+                if pos_time > average_time {
+                    time_for_each_position.push(pos_time)
+                } else {
+                    break;
                 }
-            };
+            }
 
             // ALPHA is the smoothing parameter that defines the weighting
             // and should be greater than 0 and less than 1
@@ -0,0 +1,43 @@
+use crate::models::vehicles_model::{CourierVehicle, UpsertCourierVehicle, VehicleProfile};
+use crate::repository::vehicles_repository;
+use crate::resources::postgres::DbConn;
+use crate::services::assignments_service::refresh_courier_availability;
+use crate::utils::errors::AppError;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use uuid::Uuid;
+
+// Changing vehicle changes courier's capacity,
+// so his availability is recounted in the same transaction
+pub async fn set_vehicle_profile(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    profile: VehicleProfile,
+) -> Result<CourierVehicle, AppError> {
+    let vehicle = UpsertCourierVehicle {
+        courier_uuid: courier,
+        vehicle_type: profile.vehicle_type.as_str().to_string(),
+        max_weight_kg: profile.max_weight_kg,
+        max_volume_liters: profile.max_volume_liters,
+        max_concurrent_deliveries: profile.max_concurrent_deliveries,
+    };
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let vehicle = vehicles_repository::upsert_vehicle(db_conn, vehicle).await?;
+                refresh_courier_availability(db_conn, courier).await?;
+                Ok(vehicle)
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+pub async fn get_vehicle_profile(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<CourierVehicle, AppError> {
+    vehicles_repository::select_vehicle(db_conn, courier)
+        .await
+        .map_err(AppError::db_error)?
+        .ok_or_else(|| AppError::not_found_error("Vehicle profile not found"))
+}
@@ -0,0 +1,144 @@
+use crate::models::zones_model::{UpsertZone, ZoneForm, ZoneInfo};
+use crate::repository::zones_repository;
+use crate::resources::postgres::DbConn;
+use crate::utils::errors::AppError;
+use crate::utils::geo::is_point_in_polygon;
+use diesel::result::{DatabaseErrorKind, Error};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub async fn get_zones(db_conn: &mut DbConn<'_>) -> Result<Vec<ZoneInfo>, AppError> {
+    let zones = zones_repository::select_all_zones(db_conn).await?;
+    let mut neighbours: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (zone, neighbour) in zones_repository::select_all_zone_neighbours(db_conn).await? {
+        neighbours.entry(zone).or_default().push(neighbour);
+    }
+    Ok(zones
+        .into_iter()
+        .map(|zone| {
+            let zone_neighbours = neighbours.remove(&zone.id).unwrap_or_default();
+            ZoneInfo::new(zone, zone_neighbours)
+        })
+        .collect())
+}
+
+pub async fn create_zone(db_conn: &mut DbConn<'_>, form: ZoneForm) -> Result<ZoneInfo, AppError> {
+    let zone = zones_repository::create_zone(db_conn, upsert_zone(form)?)
+        .await
+        .map_err(map_name_conflict)?;
+    Ok(ZoneInfo::new(zone, Vec::new()))
+}
+
+pub async fn update_zone(
+    db_conn: &mut DbConn<'_>,
+    zone_id: i64,
+    form: ZoneForm,
+) -> Result<ZoneInfo, AppError> {
+    let zone = zones_repository::update_zone(db_conn, zone_id, upsert_zone(form)?)
+        .await
+        .map_err(map_name_conflict)?
+        .ok_or_else(|| AppError::not_found_error("Zone not found"))?;
+    let neighbours = zones_repository::select_zone_neighbours(db_conn, zone_id).await?;
+    Ok(ZoneInfo::new(zone, neighbours))
+}
+
+// Positions in queue lose their zone, couriers lose the zone from their list
+pub async fn delete_zone(db_conn: &mut DbConn<'_>, zone_id: i64) -> Result<(), AppError> {
+    let deleted = zones_repository::delete_zone(db_conn, zone_id).await?;
+    if deleted == 0 {
+        return Err(AppError::not_found_error("Zone not found"));
+    }
+    Ok(())
+}
+
+pub async fn set_zone_neighbours(
+    db_conn: &mut DbConn<'_>,
+    zone_id: i64,
+    mut neighbours: Vec<i64>,
+) -> Result<Vec<i64>, AppError> {
+    neighbours.sort_unstable();
+    neighbours.dedup();
+    if neighbours.contains(&zone_id) {
+        return Err(AppError::validation_error(
+            "Zone cannot be a neighbour of itself",
+        ));
+    }
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let mut all_zones = neighbours.clone();
+                all_zones.push(zone_id);
+                check_zones_exist(db_conn, &all_zones).await?;
+                zones_repository::replace_zone_neighbours(db_conn, zone_id, &neighbours).await?;
+                Ok(neighbours)
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+// Couriers without zones work everywhere
+pub async fn set_courier_zones(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+    mut zones: Vec<i64>,
+) -> Result<Vec<i64>, AppError> {
+    zones.sort_unstable();
+    zones.dedup();
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                check_zones_exist(db_conn, &zones).await?;
+                zones_repository::replace_courier_zones(db_conn, courier, &zones).await?;
+                Ok(zones)
+            }
+            .scope_boxed()
+        })
+        .await
+}
+
+pub async fn get_courier_zones(
+    db_conn: &mut DbConn<'_>,
+    courier: Uuid,
+) -> Result<Vec<i64>, AppError> {
+    zones_repository::select_courier_zones(db_conn, courier)
+        .await
+        .map_err(AppError::db_error)
+}
+
+// Zone containing the point, the one created first wins if zones overlap
+pub async fn find_zone_for_point(
+    db_conn: &mut DbConn<'_>,
+    point: (f64, f64),
+) -> Result<Option<i64>, AppError> {
+    let zones = zones_repository::select_all_zones(db_conn).await?;
+    Ok(zones
+        .into_iter()
+        .find(|zone| is_point_in_polygon(point, &zone.vertices()))
+        .map(|zone| zone.id))
+}
+
+fn upsert_zone(form: ZoneForm) -> Result<UpsertZone, AppError> {
+    Ok(UpsertZone {
+        name: form.name,
+        polygon: serde_json::to_value(form.polygon).map_err(AppError::serde_error)?,
+    })
+}
+
+async fn check_zones_exist(db_conn: &mut DbConn<'_>, zones: &[i64]) -> Result<(), AppError> {
+    let existing = zones_repository::count_zones(db_conn, zones).await?;
+    if existing != zones.len() as i64 {
+        return Err(AppError::not_found_error("Zone not found"));
+    }
+    Ok(())
+}
+
+fn map_name_conflict(error: Error) -> AppError {
+    match error {
+        Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+            AppError::conflict_error("Zone with this name already exists")
+        }
+        e => AppError::db_error(e),
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+// Exponential back-off from `min` to `max`, delay is doubled on every call untill reset
+pub struct Backoff {
+    min: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl Backoff {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Backoff {
+            min,
+            max,
+            current: min,
+        }
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.current;
+        self.current = (self.current * 2).min(self.max);
+        delay
+    }
+
+    pub fn reset(&mut self) {
+        self.current = self.min;
+    }
+}
+
+// Delay before retry number `attempt` counted from 1, doubled from `min` up to `max`
+pub fn retry_delay(min: Duration, max: Duration, attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    min.saturating_mul(factor).min(max)
+}
@@ -0,0 +1,70 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+// Opens after `failure_threshold` consecutive failures and rejects calls for
+// `reset_timeout`. Then one trial call is let through, its result closes the breaker
+// or keeps it open for another `reset_timeout`
+pub struct CircuitBreaker {
+    name: String,
+    failure_threshold: u32,
+    reset_timeout: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: &str, failure_threshold: u32, reset_timeout: Duration) -> Self {
+        CircuitBreaker {
+            name: name.to_owned(),
+            failure_threshold: failure_threshold.max(1),
+            reset_timeout,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    pub fn allow_request(&self) -> bool {
+        let mut state = self.state.lock().expect("Circuit breaker lock is poisoned");
+        match state.opened_at {
+            None => true,
+            // Next trial is allowed only after another timeout,
+            // so a trial call that never finished can't block the breaker
+            Some(opened_at) if opened_at.elapsed() >= self.reset_timeout => {
+                state.opened_at = Some(Instant::now());
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().expect("Circuit breaker lock is poisoned");
+        if state.opened_at.is_some() {
+            info!("{} service is healthy again, circuit is closed", self.name);
+        }
+        *state = BreakerState::default();
+    }
+
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock().expect("Circuit breaker lock is poisoned");
+        state.consecutive_failures += 1;
+        if state.opened_at.is_some() {
+            warn!(
+                "{} service is still unhealthy, circuit stays open",
+                self.name
+            );
+            state.opened_at = Some(Instant::now());
+        } else if state.consecutive_failures >= self.failure_threshold {
+            error!(
+                "{} service is unhealthy after {} failures, circuit is open for {:?}",
+                self.name, state.consecutive_failures, self.reset_timeout
+            );
+            state.opened_at = Some(Instant::now());
+        }
+    }
+}
@@ -1,19 +1,30 @@
 use crate::middleware::logs_middleware::CustomRootSpanBuilder;
 use crate::routes::api::config;
-use crate::services::couriers_service::{check_grpc_connection, courier_distribution_loop};
+use crate::services::batch_matching_service::{BatchCostWeights, DistributionMode};
+use crate::services::couriers_service::check_grpc_connection;
+use crate::services::leader_service::distributor_election_loop;
+use crate::services::matching_service::{MatchingService, MatchingStrategyKind, MatchingWeights};
+use crate::services::outbox_service::outbox_dispatch_loop;
+use crate::services::presence_service::courier_sweeper_loop;
 use crate::services::users_service::UserService;
 use crate::utils::grpc::users_grpc::users_server::UsersServer;
 use crate::{
     middleware::logs_middleware::init_tracing_suscriber,
+    resources::documents_storage::{DocumentsStorage, LocalDocumentsStorage},
+    resources::grpc_channels::GrpcSettings,
+    resources::grpc_clients::{AnalyticsAddress, AnalyticsService, OrdersAddress, OrdersService},
     resources::postgres::{establish_connection_pool, DbPool},
 };
 use actix_web::{web, App, HttpServer};
 use dotenvy::dotenv;
+use std::sync::Arc;
+use std::time::Duration;
 use structopt::StructOpt;
 use tonic::transport::server::Router;
 use tonic::transport::Server;
 use tracing::info;
 use tracing_actix_web::TracingLogger;
+use uuid::Uuid;
 use super::permission_policy::Policy;
 
 #[derive(Debug, StructOpt, Clone)]
@@ -36,6 +47,123 @@ pub struct Opt {
     #[structopt(long, env = "CREATE_ORDER_CRONE", default_value = "300")]
     pub create_order_crone: i32,
 
+    // Longest sleep of distributor between checks if no events came
+    #[structopt(long, env = "DISTRIBUTOR_POLL_INTERVAL", default_value = "5")]
+    pub distributor_poll_interval: u64,
+
+    // Back-off in milliseconds while there is no free courier for the first in queue
+    #[structopt(long, env = "DISTRIBUTOR_MIN_BACKOFF", default_value = "100")]
+    pub distributor_min_backoff: u64,
+
+    #[structopt(long, env = "DISTRIBUTOR_MAX_BACKOFF", default_value = "5000")]
+    pub distributor_max_backoff: u64,
+
+    // One of: greedy, batch
+    #[structopt(long, env = "DISTRIBUTION_MODE", default_value = "greedy")]
+    pub distribution_mode: DistributionMode,
+
+    // Seconds between batch assignments in batch distribution mode
+    #[structopt(long, env = "BATCH_INTERVAL", default_value = "10")]
+    pub batch_interval: u64,
+
+    #[structopt(long, env = "BATCH_DISTANCE_WEIGHT", default_value = "0.5")]
+    pub batch_distance_weight: f64,
+
+    #[structopt(long, env = "BATCH_WAITING_WEIGHT", default_value = "0.3")]
+    pub batch_waiting_weight: f64,
+
+    #[structopt(long, env = "BATCH_RATING_WEIGHT", default_value = "0.2")]
+    pub batch_rating_weight: f64,
+
+    #[structopt(long, env = "COURIER_OFFER_TIMEOUT", default_value = "30")]
+    pub courier_offer_timeout: i32,
+
+    #[structopt(long, env = "COURIER_LOCATION_TTL", default_value = "60")]
+    pub courier_location_ttl: i32,
+
+    #[structopt(long, env = "MAX_COURIER_SEARCH_RADIUS", default_value = "5.0")]
+    pub max_courier_search_radius: f64,
+
+    // One of: first-free, highest-rating, least-recently-assigned, nearest, weighted
+    #[structopt(long, env = "MATCHING_STRATEGY", default_value = "nearest")]
+    pub matching_strategy: MatchingStrategyKind,
+
+    // Seconds of waiting one level of queue priority is worth
+    #[structopt(long, env = "QUEUE_PRIORITY_STEP", default_value = "60")]
+    pub queue_priority_step: i32,
+
+    // Seconds after which position is offered to couriers of neighbouring zones
+    #[structopt(long, env = "ZONE_FALLBACK_WAIT", default_value = "60")]
+    pub zone_fallback_wait: i32,
+
+    // Couriers without a planned shift covering current time are not matched
+    #[structopt(
+        long,
+        env = "STRICT_SCHEDULE_MODE",
+        default_value = "false",
+        parse(try_from_str)
+    )]
+    pub strict_schedule_mode: bool,
+
+    #[structopt(long, env = "MATCHING_RATING_WEIGHT", default_value = "0.3")]
+    pub matching_rating_weight: f64,
+
+    #[structopt(long, env = "MATCHING_FAIRNESS_WEIGHT", default_value = "0.3")]
+    pub matching_fairness_weight: f64,
+
+    #[structopt(long, env = "MATCHING_DISTANCE_WEIGHT", default_value = "0.4")]
+    pub matching_distance_weight: f64,
+
+    // Courier's rating is pulled to the prior mean untill enough ratings are collected
+    #[structopt(long, env = "RATING_PRIOR_MEAN", default_value = "5.0")]
+    pub rating_prior_mean: f64,
+
+    #[structopt(long, env = "RATING_PRIOR_WEIGHT", default_value = "5")]
+    pub rating_prior_weight: f64,
+
+    // Courier on shift who hasn't sent anything for this number of seconds is taken offline
+    #[structopt(long, env = "COURIER_HEARTBEAT_TIMEOUT", default_value = "120")]
+    pub courier_heartbeat_timeout: i32,
+
+    #[structopt(long, env = "COURIER_SWEEP_INTERVAL", default_value = "15")]
+    pub courier_sweep_interval: u64,
+
+    // Flat payment to courier for every delivered order
+    #[structopt(long, env = "DELIVERY_FEE", default_value = "5.0")]
+    pub delivery_fee: f64,
+
+    // Deliveries one courier is expected to make per hour, used by capacity forecast
+    #[structopt(long, env = "ORDERS_PER_COURIER_HOUR", default_value = "2.0")]
+    pub orders_per_courier_hour: f64,
+
+    // Seconds between outbox dispatcher runs
+    #[structopt(long, env = "OUTBOX_POLL_INTERVAL", default_value = "2")]
+    pub outbox_poll_interval: u64,
+
+    // Failed notification is marked dead after this number of delivery attempts
+    #[structopt(long, env = "OUTBOX_MAX_ATTEMPTS", default_value = "10")]
+    pub outbox_max_attempts: i32,
+
+    // Back-off in seconds between delivery attempts of one notification
+    #[structopt(long, env = "OUTBOX_MIN_BACKOFF", default_value = "1")]
+    pub outbox_min_backoff: u64,
+
+    #[structopt(long, env = "OUTBOX_MAX_BACKOFF", default_value = "600")]
+    pub outbox_max_backoff: u64,
+
+    // Seconds a dispatcher has to deliver claimed notification,
+    // after that it's claimed again by any dispatcher
+    #[structopt(long, env = "OUTBOX_LEASE_TIMEOUT", default_value = "300")]
+    pub outbox_lease_timeout: i32,
+
+    // Directory where couriers' documents are kept
+    #[structopt(long, env = "DOCUMENTS_STORAGE_PATH", default_value = "documents")]
+    pub documents_storage_path: String,
+
+    // Name of this replica shown in leader election status, random if not set
+    #[structopt(long, env = "INSTANCE_ID")]
+    pub instance_id: Option<String>,
+
     #[structopt(long, env = "BIND_ADDRESS", default_value = "0.0.0.0:8080")]
     pub bind_address: String,
 
@@ -47,14 +175,39 @@ pub struct Opt {
         env = "GRPC_ORDERS_ADDRESS",
         default_value = "http://0.0.0.0:50052"
     )]
-    pub grpc_orders_address: String,
+    pub grpc_orders_address: OrdersAddress,
 
     #[structopt(
         long,
         env = "GRPC_ANALYTICS_ADDRESS",
         default_value = "http://0.0.0.0:50053"
     )]
-    pub grpc_analytics_address: String,
+    pub grpc_analytics_address: AnalyticsAddress,
+
+    // Timeouts of calls to other services in milliseconds
+    #[structopt(long, env = "GRPC_CONNECT_TIMEOUT", default_value = "2000")]
+    pub grpc_connect_timeout: u64,
+
+    #[structopt(long, env = "GRPC_REQUEST_TIMEOUT", default_value = "5000")]
+    pub grpc_request_timeout: u64,
+
+    // Attempts of idempotent calls, back-off between them is in milliseconds
+    #[structopt(long, env = "GRPC_RETRY_ATTEMPTS", default_value = "3")]
+    pub grpc_retry_attempts: u32,
+
+    #[structopt(long, env = "GRPC_RETRY_MIN_BACKOFF", default_value = "100")]
+    pub grpc_retry_min_backoff: u64,
+
+    #[structopt(long, env = "GRPC_RETRY_MAX_BACKOFF", default_value = "2000")]
+    pub grpc_retry_max_backoff: u64,
+
+    // Consecutive failures after which calls to the service fail fast
+    #[structopt(long, env = "GRPC_BREAKER_FAILURES", default_value = "5")]
+    pub grpc_breaker_failures: u32,
+
+    // Seconds untill the next trial call to unhealthy service
+    #[structopt(long, env = "GRPC_BREAKER_RESET_TIMEOUT", default_value = "30")]
+    pub grpc_breaker_reset_timeout: u64,
 }
 
 #[derive(Clone)]
@@ -63,45 +216,149 @@ pub struct Config {
     pub jwt_secret: String,
     pub password_salt: String,
     pub password_secret_key: String,
+    pub database_url: String,
     pub db_pool: DbPool,
     pub order_max_waiting_time: i32,
     pub create_order_crone: i32,
+    pub distributor_poll_interval: u64,
+    pub distributor_min_backoff: u64,
+    pub distributor_max_backoff: u64,
+    pub distribution_mode: DistributionMode,
+    pub batch_interval: u64,
+    pub batch_cost_weights: BatchCostWeights,
+    pub courier_offer_timeout: i32,
+    pub queue_priority_step: i32,
+    pub courier_location_ttl: i32,
+    pub max_courier_search_radius: f64,
+    pub matching_service: MatchingService,
+    pub rating_prior_mean: f64,
+    pub rating_prior_weight: f64,
+    pub courier_heartbeat_timeout: i32,
+    pub courier_sweep_interval: u64,
+    pub delivery_fee: f64,
+    pub orders_per_courier_hour: f64,
+    pub outbox_poll_interval: u64,
+    pub outbox_max_attempts: i32,
+    pub outbox_min_backoff: u64,
+    pub outbox_max_backoff: u64,
+    pub outbox_lease_timeout: i32,
+    pub documents_storage: Arc<dyn DocumentsStorage>,
+    pub instance_id: String,
     pub bind_address: String,
     pub grpc_users_address: String,
-    pub grpc_orders_address: String,
-    pub grpc_analytics_address: String,
+    pub orders_grpc: OrdersService,
+    pub analytics_grpc: AnalyticsService,
 }
 
 impl Config {
     pub async fn init() -> Config {
         dotenv().ok();
         init_tracing_suscriber().await;
-        let opt = Opt::from_args();
+        Config::from_opt(Opt::from_args()).await
+    }
 
+    pub async fn from_opt(opt: Opt) -> Config {
         let permission_policy = Policy::build();
         let jwt_secret = opt.jwt_secret;
         let password_salt = opt.password_salt;
         let password_secret_key = opt.password_secret_key;
-        let db_pool = establish_connection_pool(&opt.database_url).await;
+        let database_url = opt.database_url;
+        let db_pool = establish_connection_pool(&database_url).await;
         let order_max_waiting_time = opt.order_max_waiting_time;
         let create_order_crone = opt.create_order_crone;
+        let distributor_poll_interval = opt.distributor_poll_interval;
+        let distributor_min_backoff = opt.distributor_min_backoff;
+        let distributor_max_backoff = opt.distributor_max_backoff;
+        let distribution_mode = opt.distribution_mode;
+        let batch_interval = opt.batch_interval;
+        let batch_cost_weights = BatchCostWeights {
+            distance: opt.batch_distance_weight,
+            waiting: opt.batch_waiting_weight,
+            rating: opt.batch_rating_weight,
+        };
+        let courier_offer_timeout = opt.courier_offer_timeout;
+        let queue_priority_step = opt.queue_priority_step;
+        let courier_location_ttl = opt.courier_location_ttl;
+        let max_courier_search_radius = opt.max_courier_search_radius;
+        let matching_weights = MatchingWeights {
+            rating: opt.matching_rating_weight,
+            fairness: opt.matching_fairness_weight,
+            distance: opt.matching_distance_weight,
+        };
+        let matching_service = MatchingService::new(
+            opt.matching_strategy,
+            matching_weights,
+            courier_location_ttl,
+            max_courier_search_radius,
+            opt.zone_fallback_wait,
+            opt.strict_schedule_mode,
+        );
+        let rating_prior_mean = opt.rating_prior_mean;
+        let rating_prior_weight = opt.rating_prior_weight;
+        let courier_heartbeat_timeout = opt.courier_heartbeat_timeout;
+        let courier_sweep_interval = opt.courier_sweep_interval;
+        let delivery_fee = opt.delivery_fee;
+        let orders_per_courier_hour = opt.orders_per_courier_hour;
+        let outbox_poll_interval = opt.outbox_poll_interval;
+        let outbox_max_attempts = opt.outbox_max_attempts;
+        let outbox_min_backoff = opt.outbox_min_backoff;
+        let outbox_max_backoff = opt.outbox_max_backoff;
+        let outbox_lease_timeout = opt.outbox_lease_timeout;
+        let documents_storage = Arc::new(LocalDocumentsStorage::new(opt.documents_storage_path));
+        let instance_id = opt
+            .instance_id
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
         let bind_address = opt.bind_address;
         let grpc_users_address = opt.grpc_users_address;
-        let grpc_orders_address = opt.grpc_orders_address;
-        let grpc_analytics_address = opt.grpc_analytics_address;
+        let grpc_settings = GrpcSettings {
+            connect_timeout: Duration::from_millis(opt.grpc_connect_timeout),
+            request_timeout: Duration::from_millis(opt.grpc_request_timeout),
+            retry_attempts: opt.grpc_retry_attempts,
+            retry_min_backoff: Duration::from_millis(opt.grpc_retry_min_backoff),
+            retry_max_backoff: Duration::from_millis(opt.grpc_retry_max_backoff),
+            breaker_failure_threshold: opt.grpc_breaker_failures,
+            breaker_reset_timeout: Duration::from_secs(opt.grpc_breaker_reset_timeout),
+        };
+        let orders_grpc = OrdersService::new(&opt.grpc_orders_address, grpc_settings);
+        let analytics_grpc = AnalyticsService::new(&opt.grpc_analytics_address, grpc_settings);
 
         Config {
             permission_policy,
             jwt_secret,
             password_salt,
             password_secret_key,
+            database_url,
             db_pool,
             order_max_waiting_time,
             create_order_crone,
+            distributor_poll_interval,
+            distributor_min_backoff,
+            distributor_max_backoff,
+            distribution_mode,
+            batch_interval,
+            batch_cost_weights,
+            courier_offer_timeout,
+            queue_priority_step,
+            courier_location_ttl,
+            max_courier_search_radius,
+            matching_service,
+            rating_prior_mean,
+            rating_prior_weight,
+            courier_heartbeat_timeout,
+            courier_sweep_interval,
+            delivery_fee,
+            orders_per_courier_hour,
+            outbox_poll_interval,
+            outbox_max_attempts,
+            outbox_min_backoff,
+            outbox_max_backoff,
+            outbox_lease_timeout,
+            documents_storage,
+            instance_id,
             bind_address,
             grpc_users_address,
-            grpc_orders_address,
-            grpc_analytics_address,
+            orders_grpc,
+            analytics_grpc,
         }
     }
 }
@@ -110,8 +367,19 @@ pub async fn run_courier_distributor_untill_stopped(config: Config) -> Result<()
     info!("Starting courier distribution handler.");
     check_grpc_connection(&config).await;
     let db_pool = config.db_pool.clone();
-    let db_conn = db_pool.get().await?;
-    courier_distribution_loop(config, db_conn).await
+    distributor_election_loop(config, db_pool).await
+}
+
+pub async fn run_courier_sweeper_untill_stopped(config: Config) -> Result<(), anyhow::Error> {
+    info!("Starting courier sweeper.");
+    let db_pool = config.db_pool.clone();
+    courier_sweeper_loop(config, db_pool).await
+}
+
+pub async fn run_outbox_dispatcher_untill_stopped(config: Config) -> Result<(), anyhow::Error> {
+    info!("Starting outbox dispatcher.");
+    let db_pool = config.db_pool.clone();
+    outbox_dispatch_loop(config, db_pool).await
 }
 
 pub struct JwtSecret {
@@ -164,6 +432,7 @@ impl GrpcServer {
             db_pool: config.db_pool.clone(),
             create_order_crone: config.create_order_crone,
             jwt_secret: config.jwt_secret.clone(),
+            config: config.clone(),
         };
 
         let server = Server::builder().add_service(UsersServer::new(user_service));
@@ -1,6 +1,7 @@
 use std::fmt::{self, Display};
 use actix_web::{error::ResponseError, http::StatusCode, HttpResponse};
 use serde::Serialize;
+use tonic::{Code, Status};
 use tracing::{error, Span};
 
 #[derive(Debug)]
@@ -49,6 +50,42 @@ impl AppError {
             error_type: AppErrorType::GrpcError,
         }
     }
+
+    pub fn storage_error(error: impl ToString) -> AppError {
+        error!("Error with documents storage: {:?}", error.to_string());
+        Span::current().record("error", error.to_string());
+        AppError {
+            message: Some("Internal server Error: storage error".to_string()),
+            error_type: AppErrorType::StorageError,
+        }
+    }
+
+    pub fn not_found_error(message: impl ToString) -> AppError {
+        AppError {
+            message: Some(message.to_string()),
+            error_type: AppErrorType::NotFoundError,
+        }
+    }
+
+    pub fn validation_error(message: impl ToString) -> AppError {
+        AppError {
+            message: Some(message.to_string()),
+            error_type: AppErrorType::ValidationError,
+        }
+    }
+
+    pub fn conflict_error(message: impl ToString) -> AppError {
+        AppError {
+            message: Some(message.to_string()),
+            error_type: AppErrorType::ConflictError,
+        }
+    }
+}
+
+impl From<diesel::result::Error> for AppError {
+    fn from(error: diesel::result::Error) -> Self {
+        AppError::db_error(error)
+    }
 }
 
 #[derive(Debug)]
@@ -56,6 +93,10 @@ pub enum AppErrorType {
     DbError,
     GrpcError,
     SerdeError,
+    StorageError,
+    NotFoundError,
+    ConflictError,
+    ValidationError,
 }
 
 #[derive(Serialize)]
@@ -69,6 +110,10 @@ impl ResponseError for AppError {
             AppErrorType::DbError => StatusCode::INTERNAL_SERVER_ERROR,
             AppErrorType::GrpcError => StatusCode::INTERNAL_SERVER_ERROR,
             AppErrorType::SerdeError => StatusCode::INTERNAL_SERVER_ERROR,
+            AppErrorType::StorageError => StatusCode::INTERNAL_SERVER_ERROR,
+            AppErrorType::NotFoundError => StatusCode::NOT_FOUND,
+            AppErrorType::ConflictError => StatusCode::CONFLICT,
+            AppErrorType::ValidationError => StatusCode::BAD_REQUEST,
         }
     }
 
@@ -79,6 +124,18 @@ impl ResponseError for AppError {
     }
 }
 
+impl From<AppError> for Status {
+    fn from(error: AppError) -> Self {
+        let code = match error.error_type {
+            AppErrorType::NotFoundError => Code::NotFound,
+            AppErrorType::ConflictError => Code::FailedPrecondition,
+            AppErrorType::ValidationError => Code::InvalidArgument,
+            _ => Code::Internal,
+        };
+        Status::new(code, error.message())
+    }
+}
+
 impl Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         write!(f, "{:?}", self)
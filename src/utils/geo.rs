@@ -0,0 +1,41 @@
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+// Great-circle distance between two points given as (latitude, longitude) in degrees
+pub fn haversine_distance_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (from_lat, from_lon) = (from.0.to_radians(), from.1.to_radians());
+    let (to_lat, to_lon) = (to.0.to_radians(), to.1.to_radians());
+    let d_lat = to_lat - from_lat;
+    let d_lon = to_lon - from_lon;
+
+    let a =
+        (d_lat / 2.0).sin().powi(2) + from_lat.cos() * to_lat.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+pub fn is_valid_coordinates(latitude: f64, longitude: f64) -> bool {
+    (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)
+}
+
+// Ray casting test, polygon is a closed ring of (latitude, longitude) vertices.
+// Fine for city districts where the Earth's curvature can be neglected
+pub fn is_point_in_polygon(point: (f64, f64), polygon: &[(f64, f64)]) -> bool {
+    let (lat, lon) = point;
+    let mut is_inside = false;
+    let mut previous = match polygon.last() {
+        Some(vertex) => *vertex,
+        None => return false,
+    };
+    for &current in polygon {
+        let (current_lat, current_lon) = current;
+        let (previous_lat, previous_lon) = previous;
+        if (current_lon > lon) != (previous_lon > lon) {
+            let crossing_lat = current_lat
+                + (lon - current_lon) * (previous_lat - current_lat) / (previous_lon - current_lon);
+            if lat < crossing_lat {
+                is_inside = !is_inside;
+            }
+        }
+        previous = current;
+    }
+    is_inside
+}
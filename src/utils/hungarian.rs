@@ -0,0 +1,213 @@
+// Minimum cost assignment of rows to distinct columns (Hungarian algorithm
+// with potentials, O(rows² · columns)). Matrix must have no more rows than columns,
+// returns the column chosen for every row. Ties are broken by the lowest column
+pub fn min_cost_assignment(costs: &[Vec<f64>]) -> Vec<usize> {
+    let rows = costs.len();
+    if rows == 0 {
+        return Vec::new();
+    }
+    let columns = costs[0].len();
+    assert!(
+        rows <= columns,
+        "Assignment matrix has more rows than columns"
+    );
+
+    // Everything is indexed from 1, row 0 and column 0 are the fictive ones
+    let mut row_potential = vec![0.0; rows + 1];
+    let mut column_potential = vec![0.0; columns + 1];
+    let mut column_row = vec![0usize; columns + 1];
+    let mut previous_column = vec![0usize; columns + 1];
+
+    for row in 1..=rows {
+        column_row[0] = row;
+        let mut column = 0;
+        let mut min_slack = vec![f64::INFINITY; columns + 1];
+        let mut visited = vec![false; columns + 1];
+        // Growing alternating path untill it reaches a free column
+        loop {
+            visited[column] = true;
+            let current_row = column_row[column];
+            let mut delta = f64::INFINITY;
+            let mut next_column = 0;
+            for candidate in 1..=columns {
+                if visited[candidate] {
+                    continue;
+                }
+                let slack = costs[current_row - 1][candidate - 1]
+                    - row_potential[current_row]
+                    - column_potential[candidate];
+                if slack < min_slack[candidate] {
+                    min_slack[candidate] = slack;
+                    previous_column[candidate] = column;
+                }
+                if min_slack[candidate] < delta {
+                    delta = min_slack[candidate];
+                    next_column = candidate;
+                }
+            }
+            for candidate in 0..=columns {
+                if visited[candidate] {
+                    row_potential[column_row[candidate]] += delta;
+                    column_potential[candidate] -= delta;
+                } else {
+                    min_slack[candidate] -= delta;
+                }
+            }
+            column = next_column;
+            if column_row[column] == 0 {
+                break;
+            }
+        }
+        // Flipping the path
+        while column != 0 {
+            let previous = previous_column[column];
+            column_row[column] = column_row[previous];
+            column = previous;
+        }
+    }
+
+    let mut assignment = vec![0; rows];
+    for (column, &row) in column_row.iter().enumerate().skip(1) {
+        if row != 0 {
+            assignment[row - 1] = column - 1;
+        }
+    }
+    assignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::min_cost_assignment;
+
+    fn total_cost(costs: &[Vec<f64>], assignment: &[usize]) -> f64 {
+        assignment
+            .iter()
+            .enumerate()
+            .map(|(row, &column)| costs[row][column])
+            .sum()
+    }
+
+    // Cheapest assignment found by trying every one
+    fn brute_force_cost(costs: &[Vec<f64>]) -> f64 {
+        fn search(costs: &[Vec<f64>], row: usize, used: &mut Vec<bool>) -> f64 {
+            if row == costs.len() {
+                return 0.0;
+            }
+            let mut best = f64::INFINITY;
+            for column in 0..used.len() {
+                if used[column] {
+                    continue;
+                }
+                used[column] = true;
+                best = best.min(costs[row][column] + search(costs, row + 1, used));
+                used[column] = false;
+            }
+            best
+        }
+        search(costs, 0, &mut vec![false; costs[0].len()])
+    }
+
+    // Deterministic pseudo-random costs from 0 to 99
+    fn generated_matrix(rows: usize, columns: usize, seed: u64) -> Vec<Vec<f64>> {
+        let mut state = seed;
+        (0..rows)
+            .map(|_| {
+                (0..columns)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        ((state >> 33) % 100) as f64
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn assert_distinct_columns(assignment: &[usize]) {
+        let mut columns = assignment.to_vec();
+        columns.sort_unstable();
+        columns.dedup();
+        assert_eq!(columns.len(), assignment.len(), "Column is used twice");
+    }
+
+    #[test]
+    fn finds_known_optimal_assignment() {
+        let costs = vec![
+            vec![4.0, 1.0, 3.0],
+            vec![2.0, 0.0, 5.0],
+            vec![3.0, 2.0, 2.0],
+        ];
+        assert_eq!(min_cost_assignment(&costs), vec![1, 0, 2]);
+
+        let costs = vec![
+            vec![9.0, 2.0, 7.0, 8.0],
+            vec![6.0, 4.0, 3.0, 7.0],
+            vec![5.0, 8.0, 1.0, 8.0],
+            vec![7.0, 6.0, 9.0, 4.0],
+        ];
+        let assignment = min_cost_assignment(&costs);
+        assert_eq!(assignment, vec![1, 0, 2, 3]);
+        assert_eq!(total_cost(&costs, &assignment), 13.0);
+    }
+
+    #[test]
+    fn doesnt_take_greedy_choice_when_it_is_worse() {
+        // Row 0 is cheapest in column 0, but giving it to row 1 saves more
+        let costs = vec![vec![1.0, 2.0], vec![1.0, 10.0]];
+        assert_eq!(min_cost_assignment(&costs), vec![1, 0]);
+    }
+
+    #[test]
+    fn assigns_rows_of_rectangular_matrix() {
+        let costs = vec![vec![10.0, 1.0, 10.0, 10.0], vec![10.0, 2.0, 10.0, 3.0]];
+        assert_eq!(min_cost_assignment(&costs), vec![1, 3]);
+    }
+
+    #[test]
+    fn matches_brute_force_on_generated_matrices() {
+        for seed in 0..50 {
+            for (rows, columns) in [(3, 3), (4, 6), (5, 5), (5, 7), (2, 6)] {
+                let costs = generated_matrix(rows, columns, seed);
+                let assignment = min_cost_assignment(&costs);
+                assert_eq!(assignment.len(), rows);
+                assert_distinct_columns(&assignment);
+                assert_eq!(
+                    total_cost(&costs, &assignment),
+                    brute_force_cost(&costs),
+                    "Not optimal for seed {seed}, {rows}x{columns}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn handles_negative_and_large_costs() {
+        let costs = vec![vec![-1000.0, 1e9, 0.0], vec![-999.0, -1000.0, 0.0]];
+        assert_eq!(min_cost_assignment(&costs), vec![0, 1]);
+    }
+
+    #[test]
+    fn breaks_ties_by_lowest_column() {
+        assert_eq!(min_cost_assignment(&[vec![5.0, 3.0, 3.0, 3.0]]), vec![1]);
+        let costs = vec![vec![1.0; 3], vec![1.0; 3]];
+        assert_eq!(min_cost_assignment(&costs), vec![0, 1]);
+    }
+
+    #[test]
+    fn empty_matrix_has_empty_assignment() {
+        assert!(min_cost_assignment(&[]).is_empty());
+    }
+
+    #[test]
+    fn single_row_takes_cheapest_column() {
+        assert_eq!(min_cost_assignment(&[vec![7.0, 4.0, 9.0]]), vec![1]);
+        assert_eq!(min_cost_assignment(&[vec![7.0]]), vec![0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "more rows than columns")]
+    fn rejects_more_rows_than_columns() {
+        min_cost_assignment(&[vec![1.0], vec![2.0]]);
+    }
+}
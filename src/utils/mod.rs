@@ -1,5 +1,9 @@
+pub mod backoff;
+pub mod circuit_breaker;
 pub mod configs;
 pub mod errors;
+pub mod geo;
 pub mod grpc;
+pub mod hungarian;
 pub mod permission_policy;
 pub mod validators;
@@ -8,3 +8,12 @@ pub fn validate_role(role: &str) -> Result<(), ValidationError> {
         Err(ValidationError::new("Role Validation Failed"))
     }
 }
+
+// Custom validator of status filter of outbox messages
+pub fn validate_outbox_status(status: &str) -> Result<(), ValidationError> {
+    if ["PENDING", "DISPATCHING", "DELIVERED", "DEAD"].contains(&status) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("Outbox Status Validation Failed"))
+    }
+}
@@ -0,0 +1,144 @@
+// Helpers shared by integration tests. Every test gets its own database created
+// from TEST_DATABASE_URL and migrated from scratch, tests are skipped if it isn't set
+#![allow(dead_code)]
+
+use delivery_user::models::queue_model::{AddUserToQueue, UserQueueInfo};
+use delivery_user::resources::postgres::{establish_connection_pool, DbConn, DbPool};
+use delivery_user::services::queue_service;
+use delivery_user::utils::configs::{Config, Opt};
+use diesel_async::{AsyncConnection, AsyncPgConnection, SimpleAsyncConnection};
+use std::path::Path;
+use structopt::StructOpt;
+use uuid::Uuid;
+
+pub struct TestDatabase {
+    pub url: String,
+    pub pool: DbPool,
+    admin_url: String,
+    name: String,
+}
+
+impl TestDatabase {
+    pub async fn create() -> Option<TestDatabase> {
+        let Ok(admin_url) = std::env::var("TEST_DATABASE_URL") else {
+            eprintln!("TEST_DATABASE_URL isn't set, skipping database test");
+            return None;
+        };
+        let name = format!("delivery_user_test_{}", Uuid::new_v4().simple());
+        let mut admin_conn = AsyncPgConnection::establish(&admin_url)
+            .await
+            .expect("Cannot connect to test database server");
+        admin_conn
+            .batch_execute(&format!("CREATE DATABASE {name}"))
+            .await
+            .expect("Cannot create test database");
+
+        let (server_url, _) = admin_url
+            .rsplit_once('/')
+            .expect("TEST_DATABASE_URL must end with database name");
+        let url = format!("{server_url}/{name}");
+        let mut db_conn = AsyncPgConnection::establish(&url)
+            .await
+            .expect("Cannot connect to test database");
+        run_migrations(&mut db_conn).await;
+
+        let pool = establish_connection_pool(&url).await;
+        Some(TestDatabase {
+            url,
+            pool,
+            admin_url,
+            name,
+        })
+    }
+
+    pub async fn conn(&self) -> DbConn<'_> {
+        self.pool.get().await.expect("Cannot get test connection")
+    }
+
+    pub async fn config(&self, args: &[&str]) -> Config {
+        config(&self.url, args).await
+    }
+
+    pub async fn drop(self) {
+        drop(self.pool);
+        let mut admin_conn = AsyncPgConnection::establish(&self.admin_url)
+            .await
+            .expect("Cannot connect to test database server");
+        admin_conn
+            .batch_execute(&format!("DROP DATABASE {} WITH (FORCE)", self.name))
+            .await
+            .expect("Cannot drop test database");
+    }
+}
+
+// Configuration with defaults, `args` are the command line options to override.
+// Database isn't connected untill the first query
+pub async fn config(database_url: &str, args: &[&str]) -> Config {
+    let mut all_args = vec![
+        "delivery_user",
+        "--jwt-secret",
+        "test_secret",
+        "--database-url",
+        database_url,
+    ];
+    all_args.extend_from_slice(args);
+    Config::from_opt(Opt::from_iter(all_args)).await
+}
+
+async fn run_migrations(db_conn: &mut AsyncPgConnection) {
+    let migrations_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations");
+    let mut migrations: Vec<_> = std::fs::read_dir(migrations_dir)
+        .expect("Cannot read migrations")
+        .map(|entry| entry.expect("Cannot read migration").path())
+        .filter(|path| path.is_dir())
+        .collect();
+    migrations.sort();
+    for migration in migrations {
+        let up = std::fs::read_to_string(migration.join("up.sql")).expect("Cannot read up.sql");
+        db_conn
+            .batch_execute(&up)
+            .await
+            .unwrap_or_else(|e| panic!("Migration {} failed: {e}", migration.display()));
+    }
+}
+
+async fn insert_user(db_conn: &mut DbConn<'_>, role: &str) -> Uuid {
+    let uuid = Uuid::new_v4();
+    db_conn
+        .batch_execute(&format!(
+            "INSERT INTO users (uuid, first_name, phone_number, email, password, role) \
+             VALUES ('{uuid}', 'Test', '{uuid}', '{uuid}@test.com', 'password', '{role}')"
+        ))
+        .await
+        .expect("Cannot insert user");
+    uuid
+}
+
+// Approved courier on shift waiting for orders
+pub async fn insert_free_courier(db_conn: &mut DbConn<'_>) -> Uuid {
+    let uuid = insert_user(db_conn, "COURIER").await;
+    db_conn
+        .batch_execute(&format!(
+            "INSERT INTO couriers (user_uuid, is_free, is_on_shift, onboarding_status, last_seen_at) \
+             VALUES ('{uuid}', true, true, 'APPROVED', NOW())"
+        ))
+        .await
+        .expect("Cannot insert courier");
+    uuid
+}
+
+pub async fn insert_queue_position(db_conn: &mut DbConn<'_>) -> UserQueueInfo {
+    let user_uuid = insert_user(db_conn, "USER").await;
+    let new_position = AddUserToQueue {
+        user_uuid,
+        pickup_latitude: None,
+        pickup_longitude: None,
+        order_weight_kg: None,
+        order_volume_liters: None,
+        zone_id: None,
+        priority: 0,
+    };
+    queue_service::add_queue_position(db_conn, new_position)
+        .await
+        .expect("Cannot add queue position")
+}
@@ -0,0 +1,78 @@
+// Sweeper must not take offline a courier who has sent a heartbeat
+// after he was selected as silent
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{insert_free_courier, TestDatabase};
+use delivery_user::resources::postgres::DbConn;
+use delivery_user::schema::schema::couriers;
+use delivery_user::services::presence_service::{heartbeat, mark_courier_offline};
+use diesel::prelude::*;
+use diesel_async::{RunQueryDsl, SimpleAsyncConnection};
+use uuid::Uuid;
+
+async fn insert_silent_courier(db_conn: &mut DbConn<'_>) -> Uuid {
+    let courier = insert_free_courier(db_conn).await;
+    db_conn
+        .batch_execute(&format!(
+            "UPDATE couriers SET last_seen_at = NOW() - INTERVAL '1 hour' \
+             WHERE user_uuid = '{courier}'"
+        ))
+        .await
+        .expect("Cannot update courier");
+    courier
+}
+
+async fn is_on_shift(db_conn: &mut DbConn<'_>, courier: Uuid) -> bool {
+    couriers::table
+        .find(courier)
+        .select(couriers::is_on_shift)
+        .get_result(db_conn)
+        .await
+        .expect("Cannot select courier")
+}
+
+#[tokio::test]
+async fn courier_who_sent_heartbeat_stays_online() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    let mut db_conn = db.conn().await;
+    let courier = insert_silent_courier(&mut db_conn).await;
+    let silent_since = Utc::now().naive_utc() - Duration::minutes(5);
+
+    heartbeat(&mut db_conn, courier)
+        .await
+        .expect("Cannot send heartbeat");
+    let marked = mark_courier_offline(&mut db_conn, courier, silent_since)
+        .await
+        .expect("Cannot mark courier offline");
+    assert!(!marked);
+    assert!(is_on_shift(&mut db_conn, courier).await);
+    drop(db_conn);
+    db.drop().await;
+}
+
+#[tokio::test]
+async fn silent_courier_is_taken_offline_once() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    let mut db_conn = db.conn().await;
+    let courier = insert_silent_courier(&mut db_conn).await;
+    let silent_since = Utc::now().naive_utc() - Duration::minutes(5);
+
+    let marked = mark_courier_offline(&mut db_conn, courier, silent_since)
+        .await
+        .expect("Cannot mark courier offline");
+    assert!(marked);
+    assert!(!is_on_shift(&mut db_conn, courier).await);
+
+    // Another sweeper which selected the same courier does nothing
+    let marked = mark_courier_offline(&mut db_conn, courier, silent_since)
+        .await
+        .expect("Cannot mark courier offline");
+    assert!(!marked);
+    drop(db_conn);
+    db.drop().await;
+}
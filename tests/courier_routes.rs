@@ -0,0 +1,112 @@
+// Courier's own resources under "/me/" must be reached by the courier
+// and not be captured by "/{uuid}/..." resources
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App};
+use common::{insert_free_courier, TestDatabase};
+use delivery_user::models::users_model::UserTokenGeneratorInfo;
+use delivery_user::routes::api::config::api_config;
+use delivery_user::services::auth_service::generate_token;
+use delivery_user::utils::configs::Config;
+use serde_json::Value;
+use uuid::Uuid;
+
+// GET request made by the courier with his token, response is read as json
+async fn get_as_courier(config: &Config, courier: Uuid, path: &str) -> (StatusCode, Value) {
+    let token_info = UserTokenGeneratorInfo {
+        uuid: courier,
+        role: "COURIER".to_string(),
+    };
+    let token = generate_token(token_info, config).await;
+    let jwt_secret = config.jwt_secret.clone();
+    let policy = config.permission_policy.clone();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(config.db_pool.clone()))
+            .app_data(web::Data::new(config.clone()))
+            .configure(move |cfg| api_config(cfg, jwt_secret, policy)),
+    )
+    .await;
+    let request = test::TestRequest::get()
+        .uri(path)
+        .insert_header(("authorization", format!("Bearer {token}")))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    let status = response.status();
+    let body = test::read_body(response).await;
+    let json = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    (status, json)
+}
+
+#[actix_web::test]
+async fn courier_reads_own_onboarding() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    let config = db.config(&[]).await;
+    let courier = {
+        let mut db_conn = db.conn().await;
+        insert_free_courier(&mut db_conn).await
+    };
+
+    let (status, onboarding) =
+        get_as_courier(&config, courier, "/api/v1/couriers/me/onboarding").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(onboarding["onboarding_status"], "APPROVED");
+    assert_eq!(onboarding["documents"], Value::Array(Vec::new()));
+    db.drop().await;
+}
+
+#[actix_web::test]
+async fn courier_reads_own_stats() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    let config = db.config(&[]).await;
+    let courier = {
+        let mut db_conn = db.conn().await;
+        insert_free_courier(&mut db_conn).await
+    };
+
+    let (status, stats) = get_as_courier(&config, courier, "/api/v1/couriers/me/stats").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(stats["courier_uuid"], courier.to_string());
+    assert_eq!(stats["deliveries_completed"], 0);
+    db.drop().await;
+}
+
+#[actix_web::test]
+async fn courier_reads_own_zones() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    let config = db.config(&[]).await;
+    let courier = {
+        let mut db_conn = db.conn().await;
+        insert_free_courier(&mut db_conn).await
+    };
+
+    let (status, zones) = get_as_courier(&config, courier, "/api/v1/couriers/me/zones").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(zones, Value::Array(Vec::new()));
+    db.drop().await;
+}
+
+#[actix_web::test]
+async fn courier_reads_own_planned_shifts() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    let config = db.config(&[]).await;
+    let courier = {
+        let mut db_conn = db.conn().await;
+        insert_free_courier(&mut db_conn).await
+    };
+
+    let (status, shifts) =
+        get_as_courier(&config, courier, "/api/v1/couriers/me/planned-shifts").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(shifts, Value::Array(Vec::new()));
+    db.drop().await;
+}
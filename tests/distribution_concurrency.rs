@@ -0,0 +1,177 @@
+// Several distributors working against one database must never offer
+// a position or claim a courier twice
+mod common;
+
+use common::{insert_free_courier, insert_queue_position, TestDatabase};
+use delivery_user::schema::schema::{courier_offers, couriers};
+use delivery_user::services::couriers_service::offer_waiting_positions;
+use delivery_user::services::offers_service::create_offer;
+use delivery_user::services::queue_service;
+use delivery_user::utils::errors::{AppError, AppErrorType};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const DISTRIBUTORS: usize = 4;
+const OFFER_TIMEOUT: i32 = 60;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_distributors_offer_every_position_and_courier_once() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    let config = db.config(&[]).await;
+    {
+        let mut db_conn = db.conn().await;
+        for _ in 0..20 {
+            insert_free_courier(&mut db_conn).await;
+        }
+        for _ in 0..30 {
+            insert_queue_position(&mut db_conn).await;
+        }
+    }
+
+    let mut distributors = Vec::new();
+    for _ in 0..DISTRIBUTORS {
+        let config = config.clone();
+        let pool = db.pool.clone();
+        distributors.push(tokio::spawn(async move {
+            let mut db_conn = pool.get().await.expect("Cannot get connection");
+            for _ in 0..3 {
+                let queue = queue_service::select_ordered_queue(&config, &mut db_conn)
+                    .await
+                    .expect("Cannot select queue");
+                offer_waiting_positions(&config, &mut db_conn, &queue).await;
+            }
+        }));
+    }
+    for distributor in distributors {
+        distributor.await.expect("Distributor panicked");
+    }
+
+    let mut db_conn = db.conn().await;
+    let offers: Vec<(i64, Uuid)> = courier_offers::table
+        .filter(courier_offers::status.eq("PENDING"))
+        .select((courier_offers::queue_id, courier_offers::courier_uuid))
+        .load(&mut db_conn)
+        .await
+        .expect("Cannot load offers");
+    let positions: HashSet<i64> = offers.iter().map(|(queue_id, _)| *queue_id).collect();
+    let offered_couriers: HashSet<Uuid> = offers.iter().map(|(_, courier)| *courier).collect();
+    assert_eq!(offers.len(), 20, "Every free courier must get one offer");
+    assert_eq!(positions.len(), offers.len(), "Position offered twice");
+    assert_eq!(
+        offered_couriers.len(),
+        offers.len(),
+        "Courier claimed twice"
+    );
+
+    let free_couriers: i64 = couriers::table
+        .filter(couriers::is_free.eq(true))
+        .count()
+        .get_result(&mut db_conn)
+        .await
+        .expect("Cannot count couriers");
+    assert_eq!(free_couriers, 0);
+    drop(db_conn);
+    db.drop().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_offers_of_one_position_create_single_offer() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    let position = {
+        let mut db_conn = db.conn().await;
+        Arc::new(insert_queue_position(&mut db_conn).await)
+    };
+    let mut couriers = Vec::new();
+    {
+        let mut db_conn = db.conn().await;
+        for _ in 0..8 {
+            couriers.push(insert_free_courier(&mut db_conn).await);
+        }
+    }
+
+    let mut attempts = Vec::new();
+    for courier in couriers {
+        let pool = db.pool.clone();
+        let position = Arc::clone(&position);
+        attempts.push(tokio::spawn(async move {
+            let mut db_conn = pool.get().await.expect("Cannot get connection");
+            create_offer(&mut db_conn, &position, courier, OFFER_TIMEOUT).await
+        }));
+    }
+    let mut created = 0;
+    for attempt in attempts {
+        match attempt.await.expect("Offer attempt panicked") {
+            Ok(Some(_)) => created += 1,
+            Ok(None) => panic!("Free courier must be claimed"),
+            Err(AppError {
+                error_type: AppErrorType::ConflictError,
+                ..
+            }) => {}
+            Err(e) => panic!("Unexpected error {e}"),
+        }
+    }
+    assert_eq!(created, 1);
+
+    // Couriers of failed attempts stay free
+    let mut db_conn = db.conn().await;
+    let free_couriers: i64 = couriers::table
+        .filter(couriers::is_free.eq(true))
+        .count()
+        .get_result(&mut db_conn)
+        .await
+        .expect("Cannot count couriers");
+    assert_eq!(free_couriers, 7);
+    drop(db_conn);
+    db.drop().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_offers_to_one_courier_claim_him_once() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    let mut positions = Vec::new();
+    let courier = {
+        let mut db_conn = db.conn().await;
+        for _ in 0..8 {
+            positions.push(insert_queue_position(&mut db_conn).await);
+        }
+        insert_free_courier(&mut db_conn).await
+    };
+
+    let mut attempts = Vec::new();
+    for position in positions {
+        let pool = db.pool.clone();
+        attempts.push(tokio::spawn(async move {
+            let mut db_conn = pool.get().await.expect("Cannot get connection");
+            create_offer(&mut db_conn, &position, courier, OFFER_TIMEOUT).await
+        }));
+    }
+    let mut created = 0;
+    for attempt in attempts {
+        match attempt.await.expect("Offer attempt panicked") {
+            Ok(Some(_)) => created += 1,
+            Ok(None) => {}
+            Err(e) => panic!("Unexpected error {e}"),
+        }
+    }
+    assert_eq!(created, 1);
+
+    let mut db_conn = db.conn().await;
+    let offers: i64 = courier_offers::table
+        .filter(courier_offers::courier_uuid.eq(courier))
+        .count()
+        .get_result(&mut db_conn)
+        .await
+        .expect("Cannot count offers");
+    assert_eq!(offers, 1);
+    drop(db_conn);
+    db.drop().await;
+}
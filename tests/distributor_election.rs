@@ -0,0 +1,59 @@
+// Leader whose distribution has failed must release the advisory lock,
+// otherwise the lock stays with a pooled connection and nobody distributes couriers
+mod common;
+
+use common::TestDatabase;
+use delivery_user::repository::leaders_repository::select_leader;
+use delivery_user::services::leader_service::{distributor_election_loop, DISTRIBUTOR};
+use diesel_async::pooled_connection::AsyncDieselConnectionManager;
+use diesel_async::AsyncPgConnection;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn another_instance_takes_over_after_leader_fails() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    // Leader's pool has only the connection holding the lock,
+    // so it can't get one for distribution and fails right after the election
+    let failing_pool = bb8::Pool::builder()
+        .max_size(1)
+        .connection_timeout(Duration::from_millis(200))
+        .build(AsyncDieselConnectionManager::<AsyncPgConnection>::new(
+            db.url.clone(),
+        ))
+        .await
+        .expect("Cannot build database pool");
+    let failing_config = db.config(&["--instance-id", "failing"]).await;
+    let failing = tokio::spawn(distributor_election_loop(failing_config, failing_pool));
+    wait_for_leader(&db, "failing").await;
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let config = db.config(&["--instance-id", "healthy"]).await;
+    let healthy = tokio::spawn(distributor_election_loop(config, db.pool.clone()));
+    wait_for_leader(&db, "healthy").await;
+
+    failing.abort();
+    healthy.abort();
+    let _ = failing.await;
+    let _ = healthy.await;
+    db.drop().await;
+}
+
+// Failing instance retries the election only after its heartbeat interval,
+// so the leader has to change well before that
+async fn wait_for_leader(db: &TestDatabase, instance_id: &str) {
+    for _ in 0..30 {
+        let leader = {
+            let mut db_conn = db.conn().await;
+            select_leader(&mut db_conn, DISTRIBUTOR)
+                .await
+                .expect("Cannot select leader")
+        };
+        if leader.is_some_and(|leader| leader.instance_id == instance_id) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("Instance {instance_id} hasn't become distributor leader");
+}
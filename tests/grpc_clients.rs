@@ -0,0 +1,327 @@
+// Calls to Orders and Analytics services made against in-process mock servers
+mod common;
+
+use chrono::Utc;
+use common::TestDatabase;
+use delivery_user::models::analytics_model::AnalyticsEvent;
+use delivery_user::models::outbox_model::Notification;
+use delivery_user::repository::outbox_repository;
+use delivery_user::services::couriers_service::{
+    check_grpc_connection, note_user_about_founded_courier, note_user_about_time_expiration,
+};
+use delivery_user::services::outbox_service::{dispatch_due_messages, enqueue_notification};
+use delivery_user::utils::configs::Config;
+use delivery_user::utils::grpc::analytics_grpc::analytics_server::{Analytics, AnalyticsServer};
+use delivery_user::utils::grpc::analytics_grpc::{
+    SaveDeliveryEventsRequest, SaveDeliveryEventsResponse, SaveRegRequest, SaveRegResponse,
+};
+use delivery_user::utils::grpc::orders_grpc::orders_server::{Orders, OrdersServer};
+use delivery_user::utils::grpc::orders_grpc::{
+    CourierForUserRequest, CourierForUserResponse, CourierReplacedRequest, CourierReplacedResponse,
+    SearchCancelledRequest, SearchCancelledResponse, TimeExpirationRequest, TimeExpirationResponse,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+use tonic::{Code, Request, Response, Status};
+use uuid::Uuid;
+
+// Database isn't used by the calls
+const UNUSED_DATABASE_URL: &str = "postgres://postgres@localhost:1/unused";
+
+// Orders service answering with `failures` one by one before it starts to succeed
+#[derive(Clone, Default)]
+struct MockOrders {
+    // Calls of every method, including failed ones
+    calls: Arc<AtomicUsize>,
+    failures: Arc<Mutex<Vec<Status>>>,
+    found_couriers: Arc<Mutex<Vec<CourierForUserRequest>>>,
+    expirations: Arc<Mutex<Vec<TimeExpirationRequest>>>,
+}
+
+impl MockOrders {
+    fn failing_with(failures: Vec<Status>) -> Self {
+        MockOrders {
+            failures: Arc::new(Mutex::new(failures)),
+            ..Default::default()
+        }
+    }
+
+    fn next_failure(&self) -> Option<Status> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let mut failures = self.failures.lock().unwrap();
+        if failures.is_empty() {
+            None
+        } else {
+            Some(failures.remove(0))
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Orders for MockOrders {
+    async fn notify_founded_courier(
+        &self,
+        request: Request<CourierForUserRequest>,
+    ) -> Result<Response<CourierForUserResponse>, Status> {
+        if let Some(status) = self.next_failure() {
+            return Err(status);
+        }
+        self.found_couriers
+            .lock()
+            .unwrap()
+            .push(request.into_inner());
+        Ok(Response::new(CourierForUserResponse {
+            order_created: true,
+        }))
+    }
+
+    async fn notify_expiration_time(
+        &self,
+        request: Request<TimeExpirationRequest>,
+    ) -> Result<Response<TimeExpirationResponse>, Status> {
+        self.expirations.lock().unwrap().push(request.into_inner());
+        if let Some(status) = self.next_failure() {
+            return Err(status);
+        }
+        Ok(Response::new(TimeExpirationResponse {
+            user_notified: true,
+        }))
+    }
+
+    async fn notify_search_cancelled(
+        &self,
+        _request: Request<SearchCancelledRequest>,
+    ) -> Result<Response<SearchCancelledResponse>, Status> {
+        if let Some(status) = self.next_failure() {
+            return Err(status);
+        }
+        Ok(Response::new(SearchCancelledResponse {
+            user_notified: true,
+        }))
+    }
+
+    async fn notify_courier_replaced(
+        &self,
+        _request: Request<CourierReplacedRequest>,
+    ) -> Result<Response<CourierReplacedResponse>, Status> {
+        if let Some(status) = self.next_failure() {
+            return Err(status);
+        }
+        Ok(Response::new(CourierReplacedResponse {
+            user_notified: true,
+        }))
+    }
+}
+
+#[derive(Clone, Default)]
+struct MockAnalytics {
+    registrations: Arc<Mutex<Vec<SaveRegRequest>>>,
+    event_batches: Arc<Mutex<Vec<SaveDeliveryEventsRequest>>>,
+}
+
+#[tonic::async_trait]
+impl Analytics for MockAnalytics {
+    async fn save_reg_info(
+        &self,
+        request: Request<SaveRegRequest>,
+    ) -> Result<Response<SaveRegResponse>, Status> {
+        self.registrations
+            .lock()
+            .unwrap()
+            .push(request.into_inner());
+        Ok(Response::new(SaveRegResponse {
+            record_created: true,
+        }))
+    }
+
+    async fn save_delivery_events(
+        &self,
+        request: Request<SaveDeliveryEventsRequest>,
+    ) -> Result<Response<SaveDeliveryEventsResponse>, Status> {
+        let request = request.into_inner();
+        let events_saved = request.events.len() as i32;
+        self.event_batches.lock().unwrap().push(request);
+        Ok(Response::new(SaveDeliveryEventsResponse { events_saved }))
+    }
+}
+
+// Servers listen on a free local port, their address is returned
+async fn spawn_orders(orders: MockOrders) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(
+        Server::builder()
+            .add_service(OrdersServer::new(orders))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    address
+}
+
+async fn spawn_analytics(analytics: MockAnalytics) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(
+        Server::builder()
+            .add_service(AnalyticsServer::new(analytics))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    address
+}
+
+async fn config_with_orders(database_url: &str, orders_address: &str) -> Config {
+    config_with_services(database_url, orders_address, "http://0.0.0.0:50053").await
+}
+
+async fn config_with_services(
+    database_url: &str,
+    orders_address: &str,
+    analytics_address: &str,
+) -> Config {
+    common::config(
+        database_url,
+        &[
+            "--grpc-orders-address",
+            orders_address,
+            "--grpc-analytics-address",
+            analytics_address,
+            "--grpc-retry-attempts",
+            "3",
+            "--grpc-retry-min-backoff",
+            "1",
+            "--grpc-retry-max-backoff",
+            "5",
+        ],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn founded_courier_reaches_orders_with_assignment_id() {
+    // Both services are running, so a call sent to the wrong one would succeed
+    let orders = MockOrders::default();
+    let orders_address = spawn_orders(orders.clone()).await;
+    let analytics = MockAnalytics::default();
+    let analytics_address = spawn_analytics(analytics.clone()).await;
+    let config =
+        config_with_services(UNUSED_DATABASE_URL, &orders_address, &analytics_address).await;
+    let user_uuid = Uuid::new_v4();
+    let courier_uuid = Uuid::new_v4();
+
+    let response = note_user_about_founded_courier(&config, user_uuid, courier_uuid, 4.5, 17)
+        .await
+        .expect("Orders must be notified");
+    assert!(response.into_inner().order_created);
+
+    let found_couriers = orders.found_couriers.lock().unwrap();
+    assert_eq!(found_couriers.len(), 1);
+    assert_eq!(found_couriers[0].user_uuid, user_uuid.to_string());
+    assert_eq!(found_couriers[0].courier_uuid, courier_uuid.to_string());
+    assert_eq!(found_couriers[0].courier_rating, 4.5);
+    assert_eq!(found_couriers[0].assignment_id, 17);
+    assert!(analytics.registrations.lock().unwrap().is_empty());
+    assert!(analytics.event_batches.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn call_is_retried_while_orders_is_unavailable() {
+    let orders = MockOrders::failing_with(vec![
+        Status::unavailable("starting"),
+        Status::resource_exhausted("too many requests"),
+    ]);
+    let address = spawn_orders(orders.clone()).await;
+    let config = config_with_orders(UNUSED_DATABASE_URL, &address).await;
+
+    note_user_about_time_expiration(&config, Uuid::new_v4())
+        .await
+        .expect("Third attempt must succeed");
+    assert_eq!(orders.expirations.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn errors_of_orders_service_are_not_retried() {
+    let orders = MockOrders::failing_with(vec![Status::invalid_argument("unknown user")]);
+    let address = spawn_orders(orders.clone()).await;
+    let config = config_with_orders(UNUSED_DATABASE_URL, &address).await;
+
+    let status = note_user_about_time_expiration(&config, Uuid::new_v4())
+        .await
+        .expect_err("Error must be returned");
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert_eq!(orders.expirations.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn connection_check_waits_for_orders() {
+    let address = spawn_orders(MockOrders::default()).await;
+    let config = config_with_orders(UNUSED_DATABASE_URL, &address).await;
+    tokio::time::timeout(Duration::from_secs(5), check_grpc_connection(&config))
+        .await
+        .expect("Running Orders service must be reachable");
+}
+
+#[tokio::test]
+async fn outbox_delivers_analytics_events_in_one_batch() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    let orders = MockOrders::default();
+    let orders_address = spawn_orders(orders.clone()).await;
+    let analytics = MockAnalytics::default();
+    let analytics_address = spawn_analytics(analytics.clone()).await;
+    let config = config_with_services(&db.url, &orders_address, &analytics_address).await;
+    let mut db_conn = db.conn().await;
+    let user_uuid = Uuid::new_v4();
+    let now = Utc::now().naive_utc();
+    for event_type in ["QUEUED", "COURIER_FOUND", "DELIVERED"] {
+        let event = AnalyticsEvent::new(event_type, user_uuid, now);
+        enqueue_notification(&mut db_conn, &Notification::AnalyticsEvent(event))
+            .await
+            .expect("Cannot enqueue event");
+    }
+    let registered = Notification::Registered {
+        uuid: user_uuid,
+        role: "USER".to_string(),
+        created_at: now,
+    };
+    enqueue_notification(&mut db_conn, &registered)
+        .await
+        .expect("Cannot enqueue registration");
+
+    let handled = dispatch_due_messages(&config, &mut db_conn)
+        .await
+        .expect("Cannot dispatch messages");
+    assert_eq!(handled, 4);
+
+    let messages = outbox_repository::select_outbox_messages(&mut db_conn, None, 0, 10)
+        .await
+        .expect("Cannot select messages");
+    assert!(messages.iter().all(|message| message.status == "DELIVERED"));
+    let mut event_ids: Vec<i64> = messages
+        .iter()
+        .filter(|message| message.kind == "ANALYTICS_EVENT")
+        .map(|message| message.id)
+        .collect();
+    event_ids.sort();
+
+    // Outbox ids are sent as event ids, so Analytics can drop repeated events
+    {
+        let event_batches = analytics.event_batches.lock().unwrap();
+        assert_eq!(event_batches.len(), 1);
+        let sent_ids: Vec<i64> = event_batches[0]
+            .events
+            .iter()
+            .map(|event| event.event_id)
+            .collect();
+        assert_eq!(sent_ids, event_ids);
+        let registrations = analytics.registrations.lock().unwrap();
+        assert_eq!(registrations.len(), 1);
+        assert_eq!(registrations[0].uuid, user_uuid.to_string());
+    }
+    assert_eq!(orders.calls.load(Ordering::SeqCst), 0);
+    drop(db_conn);
+    db.drop().await;
+}
@@ -0,0 +1,71 @@
+// Offer answered by the courier while it's being expired keeps the answer
+mod common;
+
+use common::{insert_free_courier, insert_queue_position, TestDatabase};
+use delivery_user::schema::schema::courier_offers;
+use delivery_user::services::offers_service::{
+    accept_offer, create_offer, expire_timed_out_offers,
+};
+use delivery_user::utils::errors::AppError;
+use diesel::prelude::*;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+use std::time::Duration;
+
+const OFFER_TIMEOUT: i32 = 1;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn offer_accepted_during_expiration_stays_accepted() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    let (offer, courier) = {
+        let mut db_conn = db.conn().await;
+        let position = insert_queue_position(&mut db_conn).await;
+        let courier = insert_free_courier(&mut db_conn).await;
+        let offer = create_offer(&mut db_conn, &position, courier, OFFER_TIMEOUT)
+            .await
+            .expect("Cannot create offer")
+            .expect("Free courier must be claimed");
+        (offer, courier)
+    };
+
+    // Acceptance is committed only after the offer has timed out,
+    // by then the expiration has already selected the offer
+    let pool = db.pool.clone();
+    let accepting = tokio::spawn(async move {
+        let mut db_conn = pool.get().await.expect("Cannot get connection");
+        db_conn
+            .transaction::<_, AppError, _>(|db_conn| {
+                async move {
+                    let assignment = accept_offer(db_conn, offer.id, courier).await?;
+                    tokio::time::sleep(Duration::from_millis(2500)).await;
+                    Ok(assignment)
+                }
+                .scope_boxed()
+            })
+            .await
+    });
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    let expired = {
+        let mut db_conn = db.conn().await;
+        expire_timed_out_offers(&mut db_conn)
+            .await
+            .expect("Cannot expire offers")
+    };
+    accepting
+        .await
+        .expect("Accepting panicked")
+        .expect("Offer must be accepted before it times out");
+    assert_eq!(expired, 0);
+
+    let mut db_conn = db.conn().await;
+    let status: String = courier_offers::table
+        .find(offer.id)
+        .select(courier_offers::status)
+        .get_result(&mut db_conn)
+        .await
+        .expect("Cannot select offer");
+    assert_eq!(status, "ACCEPTED");
+    drop(db_conn);
+    db.drop().await;
+}
@@ -0,0 +1,191 @@
+// Outbox messages are claimed with a lease and delivered outside of the claiming
+// transaction, dispatchers must never claim one message at the same time
+mod common;
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use common::TestDatabase;
+use delivery_user::models::outbox_model::{Notification, OutboxMessage, UpdateOutboxMessage};
+use delivery_user::repository::outbox_repository;
+use delivery_user::resources::postgres::DbConn;
+use delivery_user::services::outbox_service::{
+    dispatch_due_messages, enqueue_notification, replay_outbox_message,
+};
+use delivery_user::utils::errors::{AppError, AppErrorType};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+const LEASE_TIMEOUT: i64 = 300;
+
+async fn enqueue_search_expired(db_conn: &mut DbConn<'_>, count: usize) {
+    for _ in 0..count {
+        let notification = Notification::SearchExpired {
+            user_uuid: Uuid::new_v4(),
+        };
+        enqueue_notification(db_conn, &notification)
+            .await
+            .expect("Cannot enqueue notification");
+    }
+}
+
+// The same thing dispatcher does before delivering messages
+async fn claim(db_conn: &mut DbConn<'_>, now: NaiveDateTime) -> Vec<OutboxMessage> {
+    db_conn
+        .transaction::<_, AppError, _>(|db_conn| {
+            async move {
+                let messages = outbox_repository::lock_due_messages(db_conn, now, 50).await?;
+                let message_ids: Vec<i64> = messages.iter().map(|message| message.id).collect();
+                let claimed = outbox_repository::claim_outbox_messages(
+                    db_conn,
+                    &message_ids,
+                    now + Duration::seconds(LEASE_TIMEOUT),
+                )
+                .await?;
+                Ok(claimed)
+            }
+            .scope_boxed()
+        })
+        .await
+        .expect("Cannot claim messages")
+}
+
+fn delivered(message: &OutboxMessage) -> UpdateOutboxMessage {
+    UpdateOutboxMessage {
+        status: "DELIVERED".to_string(),
+        attempts: message.attempts + 1,
+        last_error: None,
+        next_attempt_at: message.next_attempt_at,
+        delivered_at: Some(Utc::now().naive_utc()),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_dispatchers_claim_every_message_once() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    {
+        let mut db_conn = db.conn().await;
+        enqueue_search_expired(&mut db_conn, 120).await;
+    }
+
+    let mut dispatchers = Vec::new();
+    for _ in 0..4 {
+        let pool = db.pool.clone();
+        dispatchers.push(tokio::spawn(async move {
+            let mut db_conn = pool.get().await.expect("Cannot get connection");
+            let mut claimed = Vec::new();
+            for _ in 0..3 {
+                claimed.extend(claim(&mut db_conn, Utc::now().naive_utc()).await);
+            }
+            claimed
+        }));
+    }
+    let mut claimed_ids = HashSet::new();
+    let mut claimed = 0;
+    for dispatcher in dispatchers {
+        for message in dispatcher.await.expect("Dispatcher panicked") {
+            assert_eq!(message.status, "DISPATCHING");
+            claimed_ids.insert(message.id);
+            claimed += 1;
+        }
+    }
+    assert_eq!(claimed, 120);
+    assert_eq!(claimed_ids.len(), claimed, "Message claimed twice");
+    db.drop().await;
+}
+
+#[tokio::test]
+async fn expired_lease_is_claimed_again_and_stale_outcome_is_ignored() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    let mut db_conn = db.conn().await;
+    enqueue_search_expired(&mut db_conn, 1).await;
+    let now = Utc::now().naive_utc();
+    let first = claim(&mut db_conn, now).await.remove(0);
+    assert!(claim(&mut db_conn, now).await.is_empty());
+
+    let after_lease = now + Duration::seconds(LEASE_TIMEOUT + 1);
+    let second = claim(&mut db_conn, after_lease).await.remove(0);
+    assert_eq!(first.id, second.id);
+
+    // Dispatcher whose lease has expired can't overwrite the outcome
+    let updated = outbox_repository::update_claimed_message(
+        &mut db_conn,
+        first.id,
+        first.next_attempt_at,
+        delivered(&first),
+    )
+    .await
+    .expect("Cannot update message");
+    assert_eq!(updated, 0);
+    let updated = outbox_repository::update_claimed_message(
+        &mut db_conn,
+        second.id,
+        second.next_attempt_at,
+        delivered(&second),
+    )
+    .await
+    .expect("Cannot update message");
+    assert_eq!(updated, 1);
+
+    // Delivered message isn't due anymore
+    let far_future = after_lease + Duration::days(1);
+    assert!(claim(&mut db_conn, far_future).await.is_empty());
+    drop(db_conn);
+    db.drop().await;
+}
+
+#[tokio::test]
+async fn message_being_dispatched_is_not_replayed() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    let mut db_conn = db.conn().await;
+    enqueue_search_expired(&mut db_conn, 1).await;
+    let message = claim(&mut db_conn, Utc::now().naive_utc()).await.remove(0);
+    match replay_outbox_message(&mut db_conn, message.id).await {
+        Err(AppError {
+            error_type: AppErrorType::ConflictError,
+            ..
+        }) => {}
+        Err(e) => panic!("Unexpected error {e}"),
+        Ok(_) => panic!("Message being dispatched must not be replayed"),
+    }
+    drop(db_conn);
+    db.drop().await;
+}
+
+#[tokio::test]
+async fn failed_delivery_is_released_for_retry() {
+    let Some(db) = TestDatabase::create().await else {
+        return;
+    };
+    // Nothing listens on this port, so every delivery fails
+    let config = db
+        .config(&[
+            "--grpc-orders-address",
+            "http://127.0.0.1:1",
+            "--grpc-retry-attempts",
+            "1",
+        ])
+        .await;
+    let mut db_conn = db.conn().await;
+    enqueue_search_expired(&mut db_conn, 2).await;
+
+    let handled = dispatch_due_messages(&config, &mut db_conn)
+        .await
+        .expect("Cannot dispatch messages");
+    assert_eq!(handled, 2);
+    let messages = outbox_repository::select_outbox_messages(&mut db_conn, None, 0, 10)
+        .await
+        .expect("Cannot select messages");
+    for message in messages {
+        assert_eq!(message.status, "PENDING");
+        assert_eq!(message.attempts, 1);
+        assert!(message.last_error.is_some());
+    }
+    drop(db_conn);
+    db.drop().await;
+}